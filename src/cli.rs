@@ -1,17 +1,62 @@
-use std::error::Error;
-use std::io;
-use clap::{Parser, Subcommand, Args};
+use std::collections::HashMap;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufReader, IsTerminal, Read, Write};
+use std::iter;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::time::Duration;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, Args, ValueEnum};
+use clap::parser::ValueSource;
+use clap_complete::{generate, Shell};
+use log::{debug, LevelFilter};
+use regex::{Regex, RegexBuilder};
+use notify::{EventKind, RecursiveMode, Watcher};
 use uuid::Uuid;
 use taskmanager::project;
 use taskmanager::config;
+use crate::settings::{self, ConfigExt};
+use crate::render;
+use crate::storage;
+use crate::ext::{ProjectDataExt, ProjectExt, TaskExt};
+use crate::error::TaskManagerError;
 
 #[derive(Parser, Debug)]
 #[command(name = "taskmanager-cli")]
-#[command(version = "1.0")]
+#[command(version)]
 #[command(about = "Manage projects and tasks", long_about = None)]
 pub struct Cli {
 	#[command(subcommand)]
 	namespace: Namespace,
+	#[arg(long, global = true, env = "TASKMANAGER_DATA_DIR")]
+	data_dir: Option<PathBuf>,
+	#[arg(long, global = true, env = "TASKMANAGER_DATA_FILE")]
+	data_file: Option<PathBuf>,
+	#[arg(long, global = true, env = "TASKMANAGER_SNAPSHOTS", default_value_t = 5)]
+	snapshots: usize,
+	#[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+	format: OutputFormat,
+	#[arg(long, global = true)]
+	force_reset: bool,
+	#[arg(long, global = true)]
+	no_color: bool,
+	#[arg(long, global = true)]
+	dry_run: bool,
+	#[arg(long, global = true)]
+	verbose: bool,
+	#[arg(long, short, global = true, conflicts_with = "verbose")]
+	quiet: bool,
+	#[arg(long, global = true, env = "TASKMANAGER_AUDIT_LOG")]
+	audit_log: bool,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+	Text,
+	Json,
 }
 
 
@@ -19,6 +64,12 @@ pub struct Cli {
 pub struct ProjectArgs {
 	#[command(subcommand)]
     command: Option<ProjectCommand>,
+	#[arg(
+		long,
+		global = true,
+		help = "Match project id arguments against exact project names instead of id prefixes (both projects for move-task and merge-projects)"
+	)]
+	by_name: bool,
 }
 
 #[derive(Debug, Subcommand, Clone)]
@@ -26,9 +77,15 @@ enum ProjectCommand {
 	Create {
 		name: String,
 		description: Option<String>,
+		#[arg(long, conflicts_with = "description")]
+		description_file: Option<PathBuf>,
+		#[arg(long)]
+		unique: bool,
 	},
 	Destroy {
 		project_id: String,
+		#[arg(long)]
+		yes: bool,
 	},
 	Update {
 		project_id: String,
@@ -36,16 +93,42 @@ enum ProjectCommand {
 		name: Option<String>,
 		#[arg(long)]
 		description: Option<String>,
+		#[arg(long, conflicts_with = "description")]
+		description_file: Option<PathBuf>,
+		#[arg(long, conflicts_with_all = ["description", "description_file"])]
+		clear_description: bool,
+	},
+	Rename {
+		project_id: String,
+		new_name: String,
+	},
+	Show {
+		project_id: String,
+	},
+	List {
+		#[arg(long, value_enum, default_value_t = ProjectSortKey::Name)]
+		sort_by: ProjectSortKey,
+		#[arg(long)]
+		name_contains: Option<String>,
+		#[arg(long)]
+		desc: bool,
+		#[command(flatten)]
+		page: PageArgs,
 	},
-	List,
 	CreateTask {
 		project_id: String,
 		name: String,
 		description: Option<String>,
+		#[arg(long, conflicts_with = "description")]
+		description_file: Option<PathBuf>,
+		#[arg(long)]
+		status: Option<String>,
 	},
 	DestroyTask {
 		project_id: String,
 		task_id: String,
+		#[arg(long)]
+		yes: bool,
 	},
 	UpdateTask {
 		project_id: String,
@@ -54,12 +137,232 @@ enum ProjectCommand {
 		name: Option<String>,
 		#[arg(long)]
 		description: Option<String>,
+		#[arg(long, conflicts_with = "description")]
+		description_file: Option<PathBuf>,
+		#[arg(long, conflicts_with_all = ["description", "description_file"])]
+		clear_description: bool,
+	},
+	RenameTask {
+		project_id: String,
+		task_id: String,
+		new_name: String,
+	},
+	ShowTask {
+		project_id: String,
+		task_id: String,
+	},
+	ListAllTasks {
+		#[arg(long, value_enum, default_value_t = TaskSortKey::Name)]
+		sort_by: TaskSortKey,
+		#[arg(long)]
+		status: Option<String>,
 	},
 	ListTasks {
 		project_id: String,
+		#[arg(long, value_enum, default_value_t = TaskSortKey::Name)]
+		sort_by: TaskSortKey,
+		#[arg(long)]
+		status: Option<String>,
+		#[arg(long, value_enum)]
+		group_by: Option<TaskGroupKey>,
+		#[command(flatten)]
+		page: PageArgs,
+	},
+	SetTaskStatus {
+		project_id: String,
+		task_id: String,
+		status: String,
+	},
+	SetStatusBulk {
+		project_id: String,
+		#[arg(long)]
+		from: String,
+		#[arg(long)]
+		to: String,
+	},
+	CompleteTask {
+		project_id: String,
+		task_id: String,
+	},
+	MoveTask {
+		task_id: String,
+		from_project: String,
+		to_project: String,
+	},
+	Export {
+		path: PathBuf,
+	},
+	ExportCsv {
+		path: PathBuf,
+	},
+	ExportOne {
+		project_id: String,
+		path: PathBuf,
+	},
+	ImportOne {
+		path: PathBuf,
+	},
+	Import {
+		path: PathBuf,
+		#[arg(long)]
+		force: bool,
+		#[arg(long, requires = "force")]
+		yes: bool,
+	},
+	Search {
+		query: String,
+		#[arg(long, value_enum, default_value_t = SearchField::All)]
+		field: SearchField,
+		#[arg(long)]
+		regex: bool,
+		#[arg(long, requires = "regex")]
+		ignore_case: bool,
+	},
+	Undo,
+	Info,
+	Stats {
+		#[arg(long)]
+		by_project: bool,
+	},
+	ImportTasks {
+		project_id: String,
+		path: PathBuf,
+		#[arg(long)]
+		strict: bool,
+	},
+	MergeProjects {
+		source: String,
+		destination: String,
+	},
+	CloneProject {
+		project_id: String,
+		new_name: String,
+	},
+	PurgeCompleted {
+		#[arg(required_unless_present = "all_projects", conflicts_with = "all_projects")]
+		project_id: Option<String>,
+		#[arg(long)]
+		all_projects: bool,
+	},
+	Watch {
+		project_id: String,
+		#[arg(long, value_enum, default_value_t = TaskSortKey::Name)]
+		sort_by: TaskSortKey,
 	},
 }
 
+impl ProjectCommand {
+	fn is_mutating(&self) -> bool {
+		!matches!(
+			self,
+			ProjectCommand::List { .. }
+				| ProjectCommand::Show { .. }
+				| ProjectCommand::ShowTask { .. }
+				| ProjectCommand::ListTasks { .. }
+				| ProjectCommand::ListAllTasks { .. }
+				| ProjectCommand::Export { .. }
+				| ProjectCommand::ExportCsv { .. }
+				| ProjectCommand::ExportOne { .. }
+				| ProjectCommand::Search { .. }
+				| ProjectCommand::Info
+				| ProjectCommand::Stats { .. }
+				| ProjectCommand::Watch { .. }
+		)
+	}
+
+	fn takes_project_id(&self) -> bool {
+		!matches!(
+			self,
+			ProjectCommand::Create { .. }
+				| ProjectCommand::List { .. }
+				| ProjectCommand::ListAllTasks { .. }
+				| ProjectCommand::Export { .. }
+				| ProjectCommand::ExportCsv { .. }
+				| ProjectCommand::ImportOne { .. }
+				| ProjectCommand::Import { .. }
+				| ProjectCommand::Search { .. }
+				| ProjectCommand::Undo
+				| ProjectCommand::Info
+				| ProjectCommand::Stats { .. }
+				| ProjectCommand::PurgeCompleted { all_projects: true, .. }
+		)
+	}
+
+	fn name(&self) -> &'static str {
+		match self {
+			ProjectCommand::Create { .. } => "create",
+			ProjectCommand::Destroy { .. } => "destroy",
+			ProjectCommand::Update { .. } => "update",
+			ProjectCommand::Rename { .. } => "rename",
+			ProjectCommand::Show { .. } => "show",
+			ProjectCommand::List { .. } => "list",
+			ProjectCommand::CreateTask { .. } => "create-task",
+			ProjectCommand::DestroyTask { .. } => "destroy-task",
+			ProjectCommand::UpdateTask { .. } => "update-task",
+			ProjectCommand::RenameTask { .. } => "rename-task",
+			ProjectCommand::ShowTask { .. } => "show-task",
+			ProjectCommand::ListAllTasks { .. } => "list-all-tasks",
+			ProjectCommand::ListTasks { .. } => "list-tasks",
+			ProjectCommand::SetTaskStatus { .. } => "set-task-status",
+			ProjectCommand::SetStatusBulk { .. } => "set-status-bulk",
+			ProjectCommand::CompleteTask { .. } => "complete-task",
+			ProjectCommand::MoveTask { .. } => "move-task",
+			ProjectCommand::Export { .. } => "export",
+			ProjectCommand::ExportCsv { .. } => "export-csv",
+			ProjectCommand::ExportOne { .. } => "export-one",
+			ProjectCommand::ImportOne { .. } => "import-one",
+			ProjectCommand::Import { .. } => "import",
+			ProjectCommand::Search { .. } => "search",
+			ProjectCommand::Undo => "undo",
+			ProjectCommand::Info => "info",
+			ProjectCommand::Stats { .. } => "stats",
+			ProjectCommand::ImportTasks { .. } => "import-tasks",
+			ProjectCommand::MergeProjects { .. } => "merge-projects",
+			ProjectCommand::CloneProject { .. } => "clone-project",
+			ProjectCommand::PurgeCompleted { .. } => "purge-completed",
+			ProjectCommand::Watch { .. } => "watch",
+		}
+	}
+}
+
+#[derive(Debug, Args, Clone, Copy, Default)]
+struct PageArgs {
+	#[arg(long)]
+	limit: Option<usize>,
+	#[arg(long, default_value_t = 0)]
+	offset: usize,
+}
+
+impl PageArgs {
+	fn apply<T>(&self, items: Vec<T>) -> Vec<T> {
+		items.into_iter().skip(self.offset).take(self.limit.unwrap_or(usize::MAX)).collect()
+	}
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ProjectSortKey {
+	Name,
+	Completion,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum TaskSortKey {
+	Name,
+	Status,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum TaskGroupKey {
+	Status,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SearchField {
+	Name,
+	Description,
+	All,
+}
+
 #[derive(Debug, Args, Clone)]
 pub struct ConfigArgs {
 	#[command(subcommand)]
@@ -74,181 +377,1536 @@ enum ConfigCommand {
 	Set {
 		key: String,
 		value: String,
-	}
+	},
+	#[command(alias = "list")]
+	Show,
+	Reset {
+		key: Option<String>,
+		#[arg(long)]
+		yes: bool,
+	},
 }
 
 #[derive(Debug, Subcommand, Clone)]
 pub enum Namespace {
 	Project(ProjectArgs),
 	Config(ConfigArgs),
+	Repl,
+	Batch {
+		path: PathBuf,
+		#[arg(long)]
+		continue_on_error: bool,
+	},
+	#[command(hide = true)]
+	Completions {
+		shell: Shell,
+	},
+}
+
+fn confirm(prompt: &str) -> io::Result<bool> {
+	if !io::stdin().is_terminal() {
+		return Ok(true);
+	}
+
+	print!("{} [y/N] ", prompt);
+	io::stdout().flush()?;
+
+	let mut answer = String::new();
+	io::stdin().read_line(&mut answer)?;
+
+	Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+fn print_completions(shell: Shell) {
+	generate(shell, &mut Cli::command(), "taskmanager-cli", &mut io::stdout());
+}
+
+fn parse_task_status(value: &str) -> Result<project::ProjectTaskStatus, TaskManagerError> {
+	match value.to_lowercase().as_str() {
+		"todo" => Ok(project::ProjectTaskStatus::Todo),
+		"in_progress" => Ok(project::ProjectTaskStatus::InProgress),
+		"complete" => Ok(project::ProjectTaskStatus::Complete),
+		_ => {
+			let message = format!("Invalid task status: {} (allowed values: todo, in_progress, complete)", value);
+			Err(TaskManagerError::InvalidInput(message))
+		},
+	}
+}
+
+fn read_description(description: &Option<String>, description_file: &Option<PathBuf>, base_dir: &Path) -> Result<Option<String>, TaskManagerError> {
+	let contents = match description_file {
+		Some(path) if path.as_os_str() == "-" => {
+			let mut contents = String::new();
+			io::stdin().read_to_string(&mut contents)?;
+			contents
+		},
+		Some(path) => fs::read_to_string(base_dir.join(path))?,
+		None => {
+			return Ok(description.clone());
+		},
+	};
+
+	Ok(Some(contents.trim_end().to_string()))
+}
+
+fn parse_task_line(line: &str) -> Result<(String, String), TaskManagerError> {
+	let (name, description) = match line.split_once('|') {
+		Some((name, description)) => (name.trim(), description.trim()),
+		None => (line.trim(), ""),
+	};
+	validate_name("Task", name)?;
+
+	Ok((name.to_string(), description.to_string()))
+}
+
+fn name_contains_ignore_case(name: &str, needle: &str) -> bool {
+	name.to_lowercase().contains(&needle.to_lowercase())
+}
+
+fn validate_name(kind: &str, name: &str) -> Result<(), TaskManagerError> {
+	if name.trim().is_empty() {
+		let message = format!("{} name must not be empty", kind);
+		return Err(TaskManagerError::InvalidInput(message));
+	}
+
+	Ok(())
+}
+
+fn status_rank(status: &project::ProjectTaskStatus) -> u8 {
+	if matches!(status, project::ProjectTaskStatus::Todo) {
+		0
+	} else if matches!(status, project::ProjectTaskStatus::InProgress) {
+		1
+	} else if matches!(status, project::ProjectTaskStatus::Complete) {
+		2
+	} else {
+		3
+	}
+}
+
+fn sort_tasks(tasks: &mut [(&Uuid, &project::ProjectTask)], sort_by: TaskSortKey) {
+	match sort_by {
+		TaskSortKey::Name => {
+			tasks.sort_by(|(a_id, a), (b_id, b)| a.name.cmp(&b.name).then(a_id.cmp(b_id)));
+		},
+		TaskSortKey::Status => {
+			tasks.sort_by(|(a_id, a), (b_id, b)| {
+				status_rank(&a.status).cmp(&status_rank(&b.status))
+					.then(a.name.cmp(&b.name))
+					.then(a_id.cmp(b_id))
+			});
+		},
+	}
+}
+
+enum QueryMatcher {
+	Substring(String),
+	Regex(Regex),
+}
+
+impl QueryMatcher {
+	fn new(query: &str, regex: bool, ignore_case: bool) -> Result<QueryMatcher, TaskManagerError> {
+		if !regex {
+			return Ok(QueryMatcher::Substring(query.to_lowercase()));
+		}
+
+		match RegexBuilder::new(query).case_insensitive(ignore_case).build() {
+			Ok(regex) => Ok(QueryMatcher::Regex(regex)),
+			Err(err) => Err(TaskManagerError::InvalidInput(format!("Invalid regex '{}': {}", query, err))),
+		}
+	}
+
+	fn is_match(&self, text: &str) -> bool {
+		match self {
+			QueryMatcher::Substring(query) => text.to_lowercase().contains(query),
+			QueryMatcher::Regex(regex) => regex.is_match(text),
+		}
+	}
+}
+
+fn filtered_tasks<'a>(project: &'a project::Project, status_filter: Option<&project::ProjectTaskStatus>, sort_by: TaskSortKey) -> Vec<(&'a Uuid, &'a project::ProjectTask)> {
+	let mut tasks: Vec<(&Uuid, &project::ProjectTask)> = project.tasks.iter()
+		.filter(|(_, task)| match status_filter {
+			Some(status) => mem::discriminant(&task.status) == mem::discriminant(status),
+			None => true,
+		})
+		.collect();
+	sort_tasks(&mut tasks, sort_by);
+
+	tasks
+}
+
+fn matches_query(name: &str, description: &str, matcher: &QueryMatcher, field: SearchField) -> bool {
+	let name_matches = matcher.is_match(name);
+	let description_matches = matcher.is_match(description);
+
+	match field {
+		SearchField::Name => name_matches,
+		SearchField::Description => description_matches,
+		SearchField::All => name_matches || description_matches,
+	}
+}
+
+fn resolve_id<'a>(kind: &str, prefix: &str, ids: impl Iterator<Item = &'a Uuid>) -> Result<Uuid, TaskManagerError> {
+	let is_valid = !prefix.is_empty()
+		&& prefix.len() <= 36
+		&& prefix.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+	if !is_valid {
+		let message = format!("'{}' is not a valid {} id", prefix, kind.to_lowercase());
+		return Err(TaskManagerError::InvalidId(message));
+	}
+	if let Ok(uuid) = Uuid::parse_str(prefix) {
+		return match ids.copied().find(|id| *id == uuid) {
+			Some(id) => Ok(id),
+			None => Err(TaskManagerError::NotFound(format!("{} not found", kind))),
+		};
+	}
+	let prefix = prefix.to_lowercase();
+
+	let mut matches: Vec<Uuid> = ids.filter(|id| id.to_string().starts_with(&prefix)).copied().collect();
+	matches.sort();
+
+	match matches.len() {
+		0 => {
+			let message = format!("{} not found", kind);
+			Err(TaskManagerError::NotFound(message))
+		},
+		1 => {
+			debug!("Resolved {} id '{}' to {}", kind.to_lowercase(), prefix, matches[0]);
+			Ok(matches[0])
+		},
+		_ => {
+			let candidates: Vec<String> = matches.iter().map(|id| id.to_string()).collect();
+			let message = format!("Ambiguous {} id '{}' matches: {}", kind.to_lowercase(), prefix, candidates.join(", "));
+			Err(TaskManagerError::InvalidInput(message))
+		},
+	}
+}
+
+fn is_data_file_change(event: &notify::Event, data_path: &Path) -> bool {
+	!matches!(event.kind, EventKind::Access(_))
+		&& event.paths.iter().any(|path| path.file_name().is_some_and(|name| Some(name) == data_path.file_name()))
+}
+
+fn load_projects(path: &Path) -> Result<project::ProjectData, TaskManagerError> {
+	if !path.exists() {
+		return Ok(project::ProjectData { projects: HashMap::new() });
+	}
+	let file = File::open(path)?;
+
+	Ok(serde_json::from_reader(BufReader::new(file))?)
+}
+
+fn open_projects(path: &Path, force_reset: bool, dry_run: bool) -> Result<project::ProjectData, TaskManagerError> {
+	match load_projects(path) {
+		Ok(projects_data) => Ok(projects_data),
+		Err(TaskManagerError::Serde(err)) => {
+			if !force_reset {
+				return Err(TaskManagerError::Corrupt(path.to_path_buf(), err));
+			}
+
+			if dry_run {
+				println!("Would move corrupt {} to {}.corrupt and start with an empty dataset", path.display(), path.display());
+				Ok(project::ProjectData { projects: HashMap::new() })
+			} else {
+				let backup_path = storage::backup_corrupt(path)?;
+				println!("Moved corrupt {} to {}", path.display(), backup_path.display());
+
+				load_projects(path)
+			}
+		},
+		Err(err) => Err(err),
+	}
+}
+
+const SESSION_FLAGS: [&str; 6] = ["data_dir", "data_file", "snapshots", "force_reset", "verbose", "audit_log"];
+
+fn parse_line(words: Vec<String>) -> Result<(Cli, ArgMatches), clap::Error> {
+	let matches = Cli::command().try_get_matches_from(iter::once("taskmanager-cli".to_string()).chain(words))?;
+	let cli = Cli::from_arg_matches(&matches)?;
+
+	Ok((cli, matches))
+}
+
+fn resolve_task_id(project: &project::Project, prefix: &str) -> Result<Uuid, TaskManagerError> {
+	resolve_id("Task", prefix, project.tasks.keys())
 }
 
 #[derive(Debug, Clone)]
 pub struct RuntimeConfig {
 	pub namespace: Namespace,
 	pub config: config::Config,
+	pub effective_config: config::Config,
 	pub projects_data: project::ProjectData,
+	pub snapshots: usize,
+	pub format: OutputFormat,
+	pub color: bool,
+	pub dry_run: bool,
+	pub quiet: bool,
+	pub audit_log: bool,
+	pub working_dir: PathBuf,
+	pub data_dir: PathBuf,
+	pub data_file: PathBuf,
+	by_name: bool,
+	projects_dirty: bool,
+	config_dirty: bool,
+	audit_ids: Vec<Uuid>,
+	pending_audit: Vec<String>,
+	data_lock: Rc<storage::DataLock>,
 }
 
 impl RuntimeConfig {
-	pub fn build() -> Result<RuntimeConfig, Box<dyn Error>> {
-		let projects_data = project::load_data()?;
-		let config = config::load_config()?;
+	pub fn build() -> Result<RuntimeConfig, TaskManagerError> {
+		let cli = Cli::try_parse()?;
+		if let Namespace::Completions { shell } = cli.namespace {
+			print_completions(shell);
+			process::exit(0);
+		}
+		let log_level = if cli.verbose { LevelFilter::Debug } else { LevelFilter::Off };
+		env_logger::Builder::new().filter_level(log_level).init();
 
-		let cli = Cli::parse();
-		match &cli.namespace {
-			Namespace::Project(_) => {
-				Ok(RuntimeConfig { namespace: cli.namespace.clone(), config, projects_data })
-			},
-			Namespace::Config(_) => {
-				Ok(RuntimeConfig { namespace: cli.namespace.clone(), config, projects_data })
-			}
+		RuntimeConfig::from_cli(cli)
+	}
+
+	fn from_cli(cli: Cli) -> Result<RuntimeConfig, TaskManagerError> {
+		let working_dir = env::current_dir()?;
+		let data_dir = match &cli.data_dir {
+			Some(data_dir) => working_dir.join(data_dir),
+			None => storage::default_data_dir()?,
+		};
+		debug!("Using data directory {}", data_dir.display());
+		fs::create_dir_all(&data_dir)?;
+		let data_file = match &cli.data_file {
+			Some(data_file) => working_dir.join(data_file),
+			None => data_dir.join(storage::PROJECTS_FILE),
+		};
+		debug!("Using data file {}", data_file.display());
+
+		debug!("Acquiring data lock");
+		let data_lock = Rc::new(storage::DataLock::acquire(&data_file)?);
+		debug!("Loading projects data");
+		let projects_data = match &cli.namespace {
+			Namespace::Config(_) => project::ProjectData { projects: HashMap::new() },
+			_ => open_projects(&data_file, cli.force_reset, cli.dry_run)?,
+		};
+		debug!("Loading config");
+		env::set_current_dir(&data_dir)?;
+		let load_result = config::load_config();
+		env::set_current_dir(&working_dir)?;
+		let config = load_result.map_err(|err| TaskManagerError::Library(err.to_string()))?;
+		let effective_config = config.with_env_overrides()?;
+		let color = !cli.no_color && env::var_os("NO_COLOR").is_none() && io::stdout().is_terminal();
+
+		Ok(RuntimeConfig {
+			namespace: cli.namespace,
+			config,
+			effective_config,
+			projects_data,
+			snapshots: cli.snapshots,
+			format: cli.format,
+			color,
+			dry_run: cli.dry_run,
+			quiet: cli.quiet,
+			audit_log: cli.audit_log,
+			working_dir,
+			data_dir,
+			data_file,
+			by_name: false,
+			projects_dirty: false,
+			config_dirty: false,
+			audit_ids: Vec::new(),
+			pending_audit: Vec::new(),
+			data_lock,
+		})
+	}
+
+	fn resolve_project_id(&self, prefix: &str) -> Result<Uuid, TaskManagerError> {
+		if self.by_name {
+			let projects = self.projects_data.find_projects_by_name(prefix);
+
+			return match projects.len() {
+				0 => {
+					let message = format!("No project named '{}'", prefix);
+					Err(TaskManagerError::NotFound(message))
+				},
+				1 => {
+					debug!("Resolved project name '{}' to {}", prefix, projects[0].id);
+					Ok(projects[0].id)
+				},
+				_ => {
+					let candidates: Vec<String> = projects.iter().map(|project| project.id.to_string()).collect();
+					let message = format!("Ambiguous project name '{}' matches: {}", prefix, candidates.join(", "));
+					Err(TaskManagerError::InvalidInput(message))
+				},
+			};
 		}
+
+		let projects = self.projects_data.get_projects();
+
+		resolve_id("Project", prefix, projects.iter().map(|project| &project.id))
 	}
 
-	pub fn persist(&self) -> Result<(), Box<dyn Error>> {
-		project::write_data(&self.projects_data)
+	pub fn persist(&mut self) -> Result<(), TaskManagerError> {
+		if self.projects_dirty {
+			debug!("Writing projects data");
+			storage::write_json_atomic(&self.data_file, &self.projects_data)?;
+			self.projects_dirty = false;
+		}
+		if self.config_dirty {
+			debug!("Writing config");
+			settings::write_config(&self.data_dir.join(settings::CONFIG_FILE), &self.config)?;
+			self.config_dirty = false;
+		}
+
+		Ok(())
 	}
 
-	pub fn run_config_command(&mut self, args: &ConfigArgs) -> Result<(), Box<dyn Error>> {
-		let config_command = &args.command.clone().unwrap();
+	pub fn run_config_command(&mut self, args: &ConfigArgs) -> Result<(), TaskManagerError> {
+		let config_command = args.command.as_ref().ok_or_else(|| {
+			TaskManagerError::InvalidInput("Missing config command".to_string())
+		})?;
 
 		match config_command {
 			ConfigCommand::Get { key } => {
-				match key.as_str() {
-					"persistence_mode" => {
-						println!("Persistence Mode: {:?}", &self.config.persistence_mode);
-					},
-					_ => {
-						println!("Invalid config key");
-					}
-				};
+				let value = self.effective_config.get(key).ok_or_else(|| settings::ConfigError::UnknownKey(key.clone()))?;
+
+				println!("{} = {}", key, value);
 			},
 			ConfigCommand::Set { key, value } => {
+				if self.dry_run {
+					self.config.clone().set(key, value)?;
+					println!("Would set config key: {} to value: {}", key, value);
+					return Ok(());
+				}
+				let previous = self.config.get(key);
+				self.config.set(key, value)?;
+				if self.config.get(key) != previous {
+					self.config_dirty = true;
+				}
+
 				println!("Setting config key: {} to value: {}", key, value);
 			},
+			ConfigCommand::Show => {
+				for (key, value) in self.effective_config.entries() {
+					println!("{} = {}", key, value);
+				}
+			},
+			ConfigCommand::Reset { key: Some(key), .. } => {
+				if self.dry_run {
+					let mut config = self.config.clone();
+					config.reset(key)?;
+					println!("Would reset config key: {} to value: {}", key, config.get(key).unwrap_or_default());
+					return Ok(());
+				}
+				let previous = self.config.get(key);
+				self.config.reset(key)?;
+				if self.config.get(key) != previous {
+					self.config_dirty = true;
+				}
+				let value = self.config.get(key).unwrap_or_default();
+
+				println!("Reset config key: {} to value: {}", key, value);
+			},
+			ConfigCommand::Reset { key: None, yes } => {
+				if self.dry_run {
+					println!("Would reset all config keys to their defaults");
+					return Ok(());
+				}
+				if !*yes && !confirm("Reset all config keys to their defaults?")? {
+					return Err(TaskManagerError::InvalidInput("Config reset cancelled".to_string()));
+				}
+				let previous = self.config.entries();
+				self.config.reset_all();
+				if self.config.entries() != previous {
+					self.config_dirty = true;
+				}
+
+				println!("Reset all config keys to their defaults");
+			},
 		}
+		self.effective_config = self.config.with_env_overrides()?;
 
 		Ok(())
 	}
 
-	pub fn run_project_command(&mut self, args: &ProjectArgs) -> Result<(), Box<dyn Error>> {
-		let project_command = &args.command.clone().unwrap();
+	pub fn run_project_command(&mut self, args: &ProjectArgs) -> Result<(), TaskManagerError> {
+		let project_command = args.command.as_ref().ok_or_else(|| {
+			TaskManagerError::InvalidInput("Missing project command".to_string())
+		})?;
+		if args.by_name && !project_command.takes_project_id() {
+			let message = format!("--by-name only applies to commands that take a project id, not project {}", project_command.name());
+			return Err(TaskManagerError::InvalidInput(message));
+		}
+		self.by_name = args.by_name;
+		self.audit_ids.clear();
 
 		match project_command {
-			ProjectCommand::Create { name, description } => {
-				let project_description = match description {
-					Some(description) => description,
-					None => &"".to_string(),
-				};
-				self.projects_data.create_project(&name, &project_description);
+			ProjectCommand::Create { name, description, description_file, unique } => {
+				validate_name("Project", name)?;
+				if *unique {
+					let projects = self.projects_data.get_projects();
+					if let Some(existing) = projects.iter().find(|project| &project.name == name) {
+						let message = format!("A project named '{}' already exists: {}", name, existing.id);
+						return Err(TaskManagerError::InvalidInput(message));
+					}
+				}
+
+				let project_description = read_description(description, description_file, &self.working_dir)?.unwrap_or_default();
+				if self.dry_run {
+					println!("Would create project '{}'", name);
+					return Ok(());
+				}
+				let project_uuid = self.projects_data.create_project(name, &project_description);
+				self.projects_dirty = true;
+				self.audit_ids.push(project_uuid);
+
+				println!("Created project {}", project_uuid);
 			},
-			ProjectCommand::Destroy { project_id } => {
-				let project_uuid = Uuid::parse_str(project_id.as_str())?;
+			ProjectCommand::Destroy { project_id, yes } => {
+				let project_uuid = self.resolve_project_id(project_id)?;
+				let (project_name, task_count) = match self.projects_data.get_project(&project_uuid) {
+					Some(project) => (project.name.clone(), project.tasks.len()),
+					None => {
+						return Err(TaskManagerError::NotFound("Project not found".to_string()));
+					},
+				};
 
-				self.projects_data.destroy_project(&project_uuid)?;
+				if self.dry_run {
+					println!("Would destroy project '{}' and {} tasks", project_name, task_count);
+					return Ok(());
+				}
+				let prompt = format!("Destroy project '{}' and {} tasks?", project_name, task_count);
+				if !*yes && !confirm(&prompt)? {
+					return Err(TaskManagerError::InvalidInput("Destroy cancelled".to_string()));
+				}
+
+				self.projects_data.destroy_project(&project_uuid).map_err(|err| TaskManagerError::Library(err.to_string()))?;
+				self.projects_dirty = true;
+				self.audit_ids.push(project_uuid);
+
+				println!("Destroyed project '{}' and {} tasks", project_name, task_count);
 			},
-			ProjectCommand::Update { project_id, name, description } => {
-				let project_uuid = Uuid::parse_str(project_id.as_str())?;
+			ProjectCommand::Update { project_id, name, description, description_file, clear_description } => {
+				if let Some(name) = name {
+					validate_name("Project", name)?;
+				}
+				let project_uuid = self.resolve_project_id(project_id)?;
+				self.audit_ids.push(project_uuid);
 				let project = self.projects_data.get_project_mut(&project_uuid).ok_or_else(|| {
-					io::Error::new(io::ErrorKind::NotFound, "Project not found")
+					TaskManagerError::NotFound("Project not found".to_string())
 				})?;
 
-				if let Some(name) = name {
+				let new_description = if *clear_description {
+					Some(String::new())
+				} else {
+					read_description(description, description_file, &self.working_dir)?
+				};
+				let new_description = new_description.filter(|description| *description != project.description);
+				let new_name = name.as_ref().filter(|name| **name != project.name);
+				if new_name.is_none() && new_description.is_none() {
+					println!("Project '{}' is unchanged", project.name);
+					return Ok(());
+				}
+				if self.dry_run {
+					let fields: Vec<&str> = [new_name.map(|_| "name"), new_description.as_ref().map(|_| "description")].into_iter().flatten().collect();
+					println!("Would update the {} of project '{}'", fields.join(" and "), project.name);
+					return Ok(());
+				}
+
+				if let Some(name) = new_name {
 					project.name = name.clone();
 				}
-				if let Some(description) = description {
-					project.description = description.clone();
+				if let Some(description) = new_description {
+					project.description = description;
 				}
+				self.projects_dirty = true;
 			},
-			ProjectCommand::List => {
-				let projects = &self.projects_data.get_projects();
+			ProjectCommand::Rename { project_id, new_name } => {
+				validate_name("Project", new_name)?;
+				let project_uuid = self.resolve_project_id(project_id)?;
+				self.audit_ids.push(project_uuid);
+				let project = self.projects_data.get_project_mut(&project_uuid).ok_or_else(|| {
+					TaskManagerError::NotFound("Project not found".to_string())
+				})?;
+				if self.dry_run {
+					println!("Would rename project '{}' to '{}'", project.name, new_name);
+					return Ok(());
+				}
+				let old_name = project.rename(new_name);
+				if old_name != *new_name {
+					self.projects_dirty = true;
+				}
 
-				println!("Projects:");
-				for project in projects {
-					println!("{}: {} - {}", project.id, project.name, project.description);
+				println!("Renamed project '{}' to '{}'", old_name, new_name);
+			},
+			ProjectCommand::Show { project_id } => {
+				let project_uuid = self.resolve_project_id(project_id)?;
+				let project = self.projects_data.get_project(&project_uuid).ok_or_else(|| {
+					TaskManagerError::NotFound("Project not found".to_string())
+				})?;
+				let view = render::ProjectView::new(project);
+
+				match self.format {
+					OutputFormat::Text => print!("{}", render::project_detail(&view)),
+					OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&view)?),
+				}
+			},
+			ProjectCommand::List { sort_by, name_contains, desc, page } => {
+				let mut projects = self.projects_data.get_projects();
+				if let Some(name_contains) = name_contains {
+					projects.retain(|project| name_contains_ignore_case(&project.name, name_contains));
+				}
+				projects.sort_by(|a, b| {
+					let ordering = match sort_by {
+						ProjectSortKey::Name => a.name.cmp(&b.name),
+						ProjectSortKey::Completion => a.completion_percent().total_cmp(&b.completion_percent()),
+					};
+					let ordering = if *desc { ordering.reverse() } else { ordering };
+
+					ordering.then(a.name.cmp(&b.name)).then(a.id.cmp(&b.id))
+				});
+
+				let total = projects.len();
+				let projects = page.apply(projects);
+				let views: Vec<render::ProjectView> = projects.iter().map(|project| render::ProjectView::new(project)).collect();
+
+				match self.format {
+					OutputFormat::Text => {
+						println!("Projects:");
+						print!("{}", render::project_lines(&views));
+						if views.len() < total {
+							println!("showing {} of {}", views.len(), total);
+						}
+					},
+					OutputFormat::Json => {
+						println!("{}", serde_json::to_string_pretty(&views)?);
+					},
 				}
 			},
-			ProjectCommand::CreateTask { project_id, name, description } => {
-				let project_uuid = Uuid::parse_str(project_id.as_str())?;
-				let task_description = match description {
-					Some(description) => description,
-					None => &"".to_string(),
+			ProjectCommand::CreateTask { project_id, name, description, description_file, status } => {
+				validate_name("Task", name)?;
+				let project_uuid = self.resolve_project_id(project_id)?;
+				let task_status = match status {
+					Some(status) => Some(parse_task_status(status)?),
+					None => None,
 				};
+				let task_description = read_description(description, description_file, &self.working_dir)?.unwrap_or_default();
 				let project = match self.projects_data.get_project_mut(&project_uuid) {
 					Some(project) => project,
 					None => {
-						return Err(Box::new(io::Error::new(io::ErrorKind::NotFound, "Project not found")));
+						return Err(TaskManagerError::NotFound("Project not found".to_string()));
 					},
 				};
 
-				project.create_task(&name, &task_description);
+				if self.dry_run {
+					println!("Would create task '{}' in project '{}'", name, project.name);
+					return Ok(());
+				}
+				let task_uuid = project.create_task(name, &task_description);
+				if let (Some(task_status), Some(task)) = (task_status, project.tasks.get_mut(&task_uuid)) {
+					task.set_status(task_status);
+				}
+				self.projects_dirty = true;
+				self.audit_ids.extend([project_uuid, task_uuid]);
+
+				println!("Created task {}", task_uuid);
 			},
-			ProjectCommand::DestroyTask { project_id, task_id } => {
-				let project_uuid = Uuid::parse_str(project_id.as_str())?;
-				let task_uuid = Uuid::parse_str(task_id.as_str())?;
+			ProjectCommand::DestroyTask { project_id, task_id, yes } => {
+				let project_uuid = self.resolve_project_id(project_id)?;
 				let project = match self.projects_data.get_project_mut(&project_uuid) {
 					Some(project) => project,
 					None => {
-						return Err(Box::new(io::Error::new(io::ErrorKind::NotFound, "Project not found")));
+						return Err(TaskManagerError::NotFound("Project not found".to_string()));
 					},
 				};
+				let task_uuid = resolve_task_id(project, task_id)?;
+				let task_name = project.tasks.get(&task_uuid).map(|task| task.name.clone()).unwrap_or_default();
+				if self.dry_run {
+					println!("Would destroy task '{}' from project '{}'", task_name, project.name);
+					return Ok(());
+				}
+				let prompt = format!("Destroy task '{}' from project '{}'?", task_name, project.name);
+				if !*yes && !confirm(&prompt)? {
+					return Err(TaskManagerError::InvalidInput("Destroy cancelled".to_string()));
+				}
 
-				project.destroy_task(&task_uuid)?;
+				project.destroy_task(&task_uuid).map_err(|err| TaskManagerError::Library(err.to_string()))?;
+				self.projects_dirty = true;
+				self.audit_ids.extend([project_uuid, task_uuid]);
 			},
-			ProjectCommand::UpdateTask { project_id, task_id, name, description } => {
-				let project_uuid = Uuid::parse_str(project_id.as_str())?;
-				let task_uuid = Uuid::parse_str(task_id.as_str())?;
+			ProjectCommand::UpdateTask { project_id, task_id, name, description, description_file, clear_description } => {
+				if let Some(name) = name {
+					validate_name("Task", name)?;
+				}
+				let project_uuid = self.resolve_project_id(project_id)?;
 				let project = match self.projects_data.get_project_mut(&project_uuid) {
 					Some(project) => project,
 					None => {
-						return Err(Box::new(io::Error::new(io::ErrorKind::NotFound, "Project not found")));
+						return Err(TaskManagerError::NotFound("Project not found".to_string()));
 					},
 				};
+				let task_uuid = resolve_task_id(project, task_id)?;
+				self.audit_ids.extend([project_uuid, task_uuid]);
 				let task = match project.tasks.get_mut(&task_uuid) {
 					Some(task) => task,
 					None => {
-						return Err(Box::new(io::Error::new(io::ErrorKind::NotFound, "Task not found")));
+						return Err(TaskManagerError::NotFound("Task not found".to_string()));
 					},
 				};
 
-				if let Some(name) = name {
+				let new_description = if *clear_description {
+					Some(String::new())
+				} else {
+					read_description(description, description_file, &self.working_dir)?
+				};
+				let new_description = new_description.filter(|description| *description != task.description);
+				let new_name = name.as_ref().filter(|name| **name != task.name);
+				if new_name.is_none() && new_description.is_none() {
+					println!("Task '{}' is unchanged", task.name);
+					return Ok(());
+				}
+				if self.dry_run {
+					let fields: Vec<&str> = [new_name.map(|_| "name"), new_description.as_ref().map(|_| "description")].into_iter().flatten().collect();
+					println!("Would update the {} of task '{}'", fields.join(" and "), task.name);
+					return Ok(());
+				}
+
+				if let Some(name) = new_name {
 					task.name = name.clone();
 				}
-				if let Some(description) = description {
-					task.description = description.clone();
+				if let Some(description) = new_description {
+					task.description = description;
 				}
+				self.projects_dirty = true;
 			},
-			ProjectCommand::ListTasks { project_id } => {
-				let project_uuid = Uuid::parse_str(project_id.as_str())?;
-				let project = match self.projects_data.get_project(&project_uuid) {
-					Some(project) => project,
-					None => {
-						return Err(Box::new(io::Error::new(io::ErrorKind::NotFound, "Project not found")));
-					},
-				};
-				println!("Project tasks:");
-
-				for (task_id, task) in &project.tasks {
-					println!("{}: {} - {}", task_id, task.name, task.description);
+			ProjectCommand::RenameTask { project_id, task_id, new_name } => {
+				validate_name("Task", new_name)?;
+				let project_uuid = self.resolve_project_id(project_id)?;
+				let project = self.projects_data.get_project_mut(&project_uuid).ok_or_else(|| {
+					TaskManagerError::NotFound("Project not found".to_string())
+				})?;
+				let task_uuid = resolve_task_id(project, task_id)?;
+				self.audit_ids.extend([project_uuid, task_uuid]);
+				let task = project.tasks.get_mut(&task_uuid).ok_or_else(|| {
+					TaskManagerError::NotFound("Task not found".to_string())
+				})?;
+				if self.dry_run {
+					println!("Would rename task '{}' to '{}'", task.name, new_name);
+					return Ok(());
+				}
+				let old_name = task.rename(new_name);
+				if old_name != *new_name {
+					self.projects_dirty = true;
 				}
-			}
-		}
 
-		Ok(())
-	}
+				println!("Renamed task '{}' to '{}'", old_name, new_name);
+			},
+			ProjectCommand::ShowTask { project_id, task_id } => {
+				let project_uuid = self.resolve_project_id(project_id)?;
+				let project = self.projects_data.get_project(&project_uuid).ok_or_else(|| {
+					TaskManagerError::NotFound("Project not found".to_string())
+				})?;
+				let task_uuid = resolve_task_id(project, task_id)?;
+				let task = project.tasks.get(&task_uuid).ok_or_else(|| {
+					TaskManagerError::NotFound("Task not found".to_string())
+				})?;
+				let view = render::TaskView::new(&task_uuid, task);
 
-	pub fn run(&mut self) -> Result<(), Box<dyn Error>> {
-		println!("Running taskmanager-cli lib version: {}", taskmanager::get_lib_version());
+				match self.format {
+					OutputFormat::Text => print!("{}", render::task_detail(&view, project)),
+					OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&view)?),
+				}
+			},
+			ProjectCommand::ListTasks { project_id, sort_by, status, group_by, page } => {
+				let project_uuid = self.resolve_project_id(project_id)?;
+				let status_filter = match status {
+					Some(status) => Some(parse_task_status(status)?),
+					None => None,
+				};
+
+				self.print_project_tasks(&project_uuid, status_filter.as_ref(), *sort_by, *group_by, *page)?;
+			},
+			ProjectCommand::ListAllTasks { sort_by, status } => {
+				let status_filter = match status {
+					Some(status) => Some(parse_task_status(status)?),
+					None => None,
+				};
+				let mut projects = self.projects_data.get_projects();
+				projects.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+
+				let mut views = Vec::new();
+				for project in projects {
+					let tasks = filtered_tasks(project, status_filter.as_ref(), *sort_by);
+					if tasks.is_empty() && status_filter.is_some() {
+						continue;
+					}
+
+					let tasks = tasks.iter().map(|(task_id, task)| render::TaskView::new(task_id, task)).collect();
+					views.push(render::ProjectTasksView { id: project.id, name: project.name.clone(), tasks });
+				}
+
+				match self.format {
+					OutputFormat::Text => {
+						for view in &views {
+							println!("Tasks for '{}' ({}): {}", view.name, view.id, view.tasks.len());
+							print!("{}", render::task_table(&view.tasks, self.color));
+						}
+					},
+					OutputFormat::Json => {
+						println!("{}", serde_json::to_string_pretty(&views)?);
+					},
+				}
+			},
+			ProjectCommand::SetTaskStatus { project_id, task_id, status } => {
+				let project_uuid = self.resolve_project_id(project_id)?;
+				let task_status = parse_task_status(status)?;
+				let project = match self.projects_data.get_project_mut(&project_uuid) {
+					Some(project) => project,
+					None => {
+						return Err(TaskManagerError::NotFound("Project not found".to_string()));
+					},
+				};
+				let task_uuid = resolve_task_id(project, task_id)?;
+				self.audit_ids.extend([project_uuid, task_uuid]);
+				let task = match project.tasks.get_mut(&task_uuid) {
+					Some(task) => task,
+					None => {
+						return Err(TaskManagerError::NotFound("Task not found".to_string()));
+					},
+				};
+
+				if self.dry_run {
+					if mem::discriminant(&task.status) == mem::discriminant(&task_status) {
+						println!("Task '{}' is already {:?}", task.name, task_status);
+					} else {
+						println!("Would set the status of task '{}' to {:?}", task.name, task_status);
+					}
+					return Ok(());
+				}
+				if task.set_status(task_status) {
+					self.projects_dirty = true;
+				}
+			},
+			ProjectCommand::SetStatusBulk { project_id, from, to } => {
+				let project_uuid = self.resolve_project_id(project_id)?;
+				let from_status = parse_task_status(from)?;
+				let to_status = parse_task_status(to)?;
+				if mem::discriminant(&from_status) == mem::discriminant(&to_status) {
+					let message = format!("--from and --to are both {:?}", from_status);
+					return Err(TaskManagerError::InvalidInput(message));
+				}
+				let project = self.projects_data.get_project_mut(&project_uuid).ok_or_else(|| {
+					TaskManagerError::NotFound("Project not found".to_string())
+				})?;
+
+				if self.dry_run {
+					let count = project.count_with_status(&from_status);
+					println!("Would change {} tasks in '{}' from {:?} to {:?}", count, project.name, from_status, to_status);
+					return Ok(());
+				}
+
+				let changed = project.set_status_bulk(&from_status, &to_status);
+				if changed > 0 {
+					self.projects_dirty = true;
+				}
+				self.audit_ids.push(project_uuid);
+
+				println!("Changed {} tasks in '{}' from {:?} to {:?}", changed, project.name, from_status, to_status);
+			},
+			ProjectCommand::CompleteTask { project_id, task_id } => {
+				let project_uuid = self.resolve_project_id(project_id)?;
+				let project = self.projects_data.get_project_mut(&project_uuid).ok_or_else(|| {
+					TaskManagerError::NotFound("Project not found".to_string())
+				})?;
+				let task_uuid = resolve_task_id(project, task_id)?;
+				self.audit_ids.extend([project_uuid, task_uuid]);
+				let task = project.tasks.get_mut(&task_uuid).ok_or_else(|| {
+					TaskManagerError::NotFound("Task not found".to_string())
+				})?;
+
+				if self.dry_run && !matches!(task.status, project::ProjectTaskStatus::Complete) {
+					println!("Would complete task '{}'", task.name);
+					return Ok(());
+				}
+				if task.set_status(project::ProjectTaskStatus::Complete) {
+					self.projects_dirty = true;
+				} else {
+					println!("Task '{}' is already complete", task.name);
+				}
+			},
+			ProjectCommand::MoveTask { task_id, from_project, to_project } => {
+				let from_uuid = self.resolve_project_id(from_project)?;
+				let to_uuid = self.resolve_project_id(to_project)?;
+				if from_uuid == to_uuid {
+					return Err(TaskManagerError::InvalidInput("Source and destination project are the same".to_string()));
+				}
+
+				let source = self.projects_data.get_project(&from_uuid).ok_or_else(|| {
+					TaskManagerError::NotFound("Project not found".to_string())
+				})?;
+				let destination = self.projects_data.get_project(&to_uuid).ok_or_else(|| {
+					TaskManagerError::NotFound("Project not found".to_string())
+				})?;
+				let task_uuid = resolve_task_id(source, task_id)?;
+				if self.dry_run {
+					let task_name = source.tasks.get(&task_uuid).map(|task| task.name.as_str()).unwrap_or_default();
+					println!("Would move task '{}' from '{}' to '{}'", task_name, source.name, destination.name);
+					return Ok(());
+				}
+
+				if !self.projects_data.move_task(&task_uuid, &from_uuid, &to_uuid) {
+					return Err(TaskManagerError::NotFound("Task not found".to_string()));
+				}
+				self.projects_dirty = true;
+				self.audit_ids.extend([from_uuid, to_uuid, task_uuid]);
+			},
+			ProjectCommand::Export { path } => {
+				let path = self.working_dir.join(path);
+				if self.dry_run {
+					println!("Would export {} projects to {}", self.projects_data.projects.len(), path.display());
+					return Ok(());
+				}
+				storage::write_json_atomic(&path, &self.projects_data)?;
+
+				println!("Exported {} projects to {}", self.projects_data.projects.len(), path.display());
+			},
+			ProjectCommand::ExportCsv { path } => {
+				let path = self.working_dir.join(path);
+				let mut projects = self.projects_data.get_projects();
+				projects.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+				if self.dry_run {
+					let task_count: usize = projects.iter().map(|project| project.tasks.len()).sum();
+					println!("Would export {} tasks to {}", task_count, path.display());
+					return Ok(());
+				}
+
+				let mut rows = Vec::new();
+				for project in projects {
+					let mut tasks: Vec<(&Uuid, &project::ProjectTask)> = project.tasks.iter().collect();
+					sort_tasks(&mut tasks, TaskSortKey::Name);
+
+					for (task_id, task) in tasks {
+						rows.push((project.name.as_str(), render::TaskView::new(task_id, task)));
+					}
+				}
+				render::tasks_csv(File::create(&path)?, &rows)?;
+
+				println!("Exported {} tasks to {}", rows.len(), path.display());
+			},
+			ProjectCommand::ExportOne { project_id, path } => {
+				let project_uuid = self.resolve_project_id(project_id)?;
+				let project = self.projects_data.get_project(&project_uuid).ok_or_else(|| {
+					TaskManagerError::NotFound("Project not found".to_string())
+				})?;
+				let path = self.working_dir.join(path);
+				if self.dry_run {
+					println!("Would export project '{}' with {} tasks to {}", project.name, project.tasks.len(), path.display());
+					return Ok(());
+				}
+				storage::write_json_atomic(&path, project)?;
+
+				println!("Exported project '{}' with {} tasks to {}", project.name, project.tasks.len(), path.display());
+			},
+			ProjectCommand::ImportOne { path } => {
+				let path = self.working_dir.join(path);
+				let file = File::open(&path)?;
+				let mut imported: project::Project = serde_json::from_reader(BufReader::new(file))?;
+				validate_name("Project", &imported.name)?;
+				if self.dry_run {
+					println!("Would import project '{}' with {} tasks from {}", imported.name, imported.tasks.len(), path.display());
+					return Ok(());
+				}
+
+				let project_uuid = Uuid::new_v4();
+				imported.id = project_uuid;
+				let (name, task_count) = (imported.name.clone(), imported.tasks.len());
+				self.projects_data.projects.insert(project_uuid, imported);
+				self.projects_dirty = true;
+				self.audit_ids.push(project_uuid);
+
+				println!("Imported project '{}' with {} tasks as {}", name, task_count, project_uuid);
+			},
+			ProjectCommand::Import { path, force, yes } => {
+				let path = self.working_dir.join(path);
+				let file = File::open(&path)?;
+				let imported: project::ProjectData = serde_json::from_reader(BufReader::new(file))?;
+
+				if *force {
+					if self.dry_run {
+						println!(
+							"Would replace all {} projects with the {} projects in {}",
+							self.projects_data.projects.len(), imported.projects.len(), path.display()
+						);
+						return Ok(());
+					}
+					let prompt = format!(
+						"Replace all {} projects with the {} projects in {}?",
+						self.projects_data.projects.len(), imported.projects.len(), path.display()
+					);
+					if !*yes && !confirm(&prompt)? {
+						return Err(TaskManagerError::InvalidInput("Import cancelled".to_string()));
+					}
+
+					println!("Imported {} projects from {}", imported.projects.len(), path.display());
+
+					self.audit_ids.extend(imported.projects.keys().copied());
+					self.projects_data = imported;
+					self.projects_dirty = true;
+				} else if self.dry_run {
+					let added = imported.projects.keys().filter(|project_id| !self.projects_data.projects.contains_key(project_id)).count();
+					println!("Would import {} new projects from {}", added, path.display());
+				} else {
+					let mut added = 0;
+					for (project_id, project) in imported.projects {
+						if !self.projects_data.projects.contains_key(&project_id) {
+							self.projects_data.projects.insert(project_id, project);
+							self.audit_ids.push(project_id);
+							added += 1;
+						}
+					}
+
+					if added > 0 {
+						self.projects_dirty = true;
+					}
+
+					println!("Imported {} new projects from {}", added, path.display());
+				}
+			},
+			ProjectCommand::Search { query, field, regex, ignore_case } => {
+				let matcher = QueryMatcher::new(query, *regex, *ignore_case)?;
+				let mut projects = self.projects_data.get_projects();
+				projects.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+
+				let mut found = false;
+				for project in projects {
+					let project_matches = matches_query(&project.name, &project.description, &matcher, *field);
+					let mut tasks: Vec<(&Uuid, &project::ProjectTask)> = project.tasks.iter()
+						.filter(|(_, task)| matches_query(&task.name, &task.description, &matcher, *field))
+						.collect();
+					if !project_matches && tasks.is_empty() {
+						continue;
+					}
+					sort_tasks(&mut tasks, TaskSortKey::Name);
+
+					found = true;
+					println!("{}: {} - {}", project.id, project.name, project.description);
+					for (task_id, task) in tasks {
+						println!("  {}: {} - {}", task_id, task.name, task.description);
+					}
+				}
+
+				if !found {
+					println!("No matches for '{}'", query);
+				}
+			},
+			ProjectCommand::Undo => {
+				if self.is_dirty() {
+					return Err(TaskManagerError::InvalidInput("Undo would discard unsaved changes".to_string()));
+				}
+				if self.dry_run {
+					println!("Would restore {} from its most recent snapshot", self.data_file.display());
+					return Ok(());
+				}
+				if !storage::restore_snapshot(&self.data_file)? {
+					return Err(TaskManagerError::NotFound("Nothing to undo".to_string()));
+				}
+				self.projects_data = load_projects(&self.data_file)?;
+				self.projects_dirty = false;
+
+				println!("Restored previous projects data");
+			},
+			ProjectCommand::ImportTasks { project_id, path, strict } => {
+				let project_uuid = self.resolve_project_id(project_id)?;
+				let contents = fs::read_to_string(self.working_dir.join(path))?;
+
+				let mut new_tasks = Vec::new();
+				let mut skipped = 0;
+				for (index, line) in contents.lines().enumerate() {
+					if line.trim().is_empty() {
+						continue;
+					}
+
+					match parse_task_line(line) {
+						Ok(task) => new_tasks.push(task),
+						Err(err) if *strict => {
+							let message = format!("Line {}: {}", index + 1, err);
+							return Err(TaskManagerError::InvalidInput(message));
+						},
+						Err(err) => {
+							println!("Skipping line {}: {}", index + 1, err);
+							skipped += 1;
+						},
+					}
+				}
+
+				let project = self.projects_data.get_project_mut(&project_uuid).ok_or_else(|| {
+					TaskManagerError::NotFound("Project not found".to_string())
+				})?;
+				if self.dry_run {
+					println!("Would create {} tasks in project '{}' ({} lines skipped)", new_tasks.len(), project.name, skipped);
+					return Ok(());
+				}
+				self.audit_ids.push(project_uuid);
+				for (name, description) in &new_tasks {
+					let task_uuid = project.create_task(name, description);
+					self.audit_ids.push(task_uuid);
+				}
+				if !new_tasks.is_empty() {
+					self.projects_dirty = true;
+				}
+
+				println!("Created {} tasks in project '{}' ({} lines skipped)", new_tasks.len(), project.name, skipped);
+			},
+			ProjectCommand::MergeProjects { source, destination } => {
+				let source_uuid = self.resolve_project_id(source)?;
+				let destination_uuid = self.resolve_project_id(destination)?;
+				if source_uuid == destination_uuid {
+					return Err(TaskManagerError::InvalidInput("Source and destination project are the same".to_string()));
+				}
+
+				let source_project = self.projects_data.get_project(&source_uuid).ok_or_else(|| {
+					TaskManagerError::NotFound("Project not found".to_string())
+				})?;
+				let destination_project = self.projects_data.get_project(&destination_uuid).ok_or_else(|| {
+					TaskManagerError::NotFound("Project not found".to_string())
+				})?;
+				if self.dry_run {
+					println!(
+						"Would merge {} tasks from '{}' into '{}' and destroy '{}'",
+						source_project.tasks.len(), source_project.name, destination_project.name, source_project.name
+					);
+					return Ok(());
+				}
+				let (source_name, destination_name) = (source_project.name.clone(), destination_project.name.clone());
+
+				let task_count = self.projects_data.merge_projects(&source_uuid, &destination_uuid).ok_or_else(|| {
+					TaskManagerError::NotFound("Project not found".to_string())
+				})?;
+				self.projects_dirty = true;
+				self.audit_ids.extend([source_uuid, destination_uuid]);
+
+				println!("Merged {} tasks from '{}' into '{}'", task_count, source_name, destination_name);
+			},
+			ProjectCommand::CloneProject { project_id, new_name } => {
+				validate_name("Project", new_name)?;
+				let project_uuid = self.resolve_project_id(project_id)?;
+				let source = self.projects_data.get_project(&project_uuid).ok_or_else(|| {
+					TaskManagerError::NotFound("Project not found".to_string())
+				})?;
+				if self.dry_run {
+					println!("Would clone project '{}' with {} tasks as '{}'", source.name, source.tasks.len(), new_name);
+					return Ok(());
+				}
+				let (source_name, task_count) = (source.name.clone(), source.tasks.len());
+
+				let clone_uuid = self.projects_data.clone_project(&project_uuid, new_name).ok_or_else(|| {
+					TaskManagerError::NotFound("Project not found".to_string())
+				})?;
+				self.projects_dirty = true;
+				self.audit_ids.extend([project_uuid, clone_uuid]);
+
+				println!("Cloned project '{}' with {} tasks as {}", source_name, task_count, clone_uuid);
+			},
+			ProjectCommand::PurgeCompleted { project_id, all_projects } => {
+				let mut project_uuids = match project_id {
+					Some(project_id) if !*all_projects => vec![self.resolve_project_id(project_id)?],
+					_ => self.projects_data.projects.keys().copied().collect(),
+				};
+				project_uuids.sort_by(|a, b| self.projects_data.projects[a].name.cmp(&self.projects_data.projects[b].name).then(a.cmp(b)));
+
+				let mut removed = 0;
+				for project_uuid in &project_uuids {
+					let project = self.projects_data.get_project_mut(project_uuid).ok_or_else(|| {
+						TaskManagerError::NotFound("Project not found".to_string())
+					})?;
+
+					if self.dry_run {
+						let (complete, _) = project.completion_stats();
+						println!("Would remove {} completed tasks from '{}'", complete, project.name);
+						removed += complete;
+					} else {
+						let purged = project.purge_completed();
+						if purged > 0 {
+							self.audit_ids.push(*project_uuid);
+						}
+						println!("Removed {} completed tasks from '{}'", purged, project.name);
+						removed += purged;
+					}
+				}
+
+				if removed > 0 && !self.dry_run {
+					self.projects_dirty = true;
+				}
+				if project_uuids.len() > 1 {
+					println!("{} completed tasks in total", removed);
+				}
+			},
+			ProjectCommand::Watch { project_id, sort_by } => {
+				let project_uuid = self.resolve_project_id(project_id)?;
+
+				self.watch_project_tasks(&project_uuid, *sort_by)?;
+			},
+			ProjectCommand::Info => {
+				let config_file = self.data_dir.join(settings::CONFIG_FILE);
+				let stats = self.projects_data.stats();
+				let info = render::InfoView {
+					version: env!("CARGO_PKG_VERSION").to_string(),
+					lib_version: taskmanager::get_lib_version().to_string(),
+					data_dir: self.data_dir.clone(),
+					data_file: self.data_file.clone(),
+					data_file_exists: self.data_file.exists(),
+					config_file_exists: config_file.exists(),
+					config_file,
+					projects: stats.projects,
+					tasks: stats.tasks,
+				};
+
+				match self.format {
+					OutputFormat::Text => print!("{}", render::info(&info)),
+					OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&info)?),
+				}
+			},
+			ProjectCommand::Stats { by_project } => {
+				if *by_project {
+					let stats = self.projects_data.stats_by_project();
+
+					match self.format {
+						OutputFormat::Text => print!("{}", render::project_stats_table(&stats)),
+						OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&stats)?),
+					}
+				} else {
+					let stats = self.projects_data.stats();
+
+					match self.format {
+						OutputFormat::Text => print!("{}", render::dataset_stats(&stats)),
+						OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&stats)?),
+					}
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	fn print_project_tasks(&self, project_uuid: &Uuid, status_filter: Option<&project::ProjectTaskStatus>, sort_by: TaskSortKey, group_by: Option<TaskGroupKey>, page: PageArgs) -> Result<(), TaskManagerError> {
+		let project = match self.projects_data.get_project(project_uuid) {
+			Some(project) => project,
+			None => {
+				return Err(TaskManagerError::NotFound("Project not found".to_string()));
+			},
+		};
+		let tasks = filtered_tasks(project, status_filter, sort_by);
+		let total = tasks.len();
+		let tasks = page.apply(tasks);
+
+		let views: Vec<render::TaskView> = tasks.iter().map(|(task_id, task)| render::TaskView::new(task_id, task)).collect();
+
+		let shown = views.len();
+
+		match (self.format, group_by) {
+			(OutputFormat::Text, None) => {
+				println!("Tasks for '{}' ({}): {}", project.name, project.id, total);
+				print!("{}", render::task_table(&views, self.color));
+			},
+			(OutputFormat::Text, Some(TaskGroupKey::Status)) => {
+				println!("Tasks for '{}' ({}): {}", project.name, project.id, total);
+				print!("{}", render::grouped_task_table(&render::group_by_status(views), self.color));
+			},
+			(OutputFormat::Json, None) => {
+				println!("{}", serde_json::to_string_pretty(&views)?);
+			},
+			(OutputFormat::Json, Some(TaskGroupKey::Status)) => {
+				println!("{}", serde_json::to_string_pretty(&render::group_by_status(views))?);
+			},
+		}
+		if matches!(self.format, OutputFormat::Text) && shown < total {
+			println!("showing {} of {}", shown, total);
+		}
+
+		Ok(())
+	}
+
+	fn watch_project_tasks(&mut self, project_uuid: &Uuid, sort_by: TaskSortKey) -> Result<(), TaskManagerError> {
+		self.data_lock.release()?;
+
+		let (sender, receiver) = mpsc::channel();
+		let mut watcher = notify::recommended_watcher(sender)?;
+		let data_path = self.data_file.clone();
+		let watch_dir = data_path.parent().unwrap_or(&self.data_dir);
+		watcher.watch(watch_dir, RecursiveMode::NonRecursive)?;
+		let clear_screen = io::stdout().is_terminal();
+
+		loop {
+			if clear_screen {
+				print!("\x1b[2J\x1b[H");
+			}
+			if let Err(err) = self.print_project_tasks(project_uuid, None, sort_by, None, PageArgs::default()) {
+				println!("Error: {err}");
+			}
+			if !self.quiet {
+				eprintln!("Watching {} for changes, press Ctrl-C to stop", data_path.display());
+			}
+			io::stdout().flush()?;
+
+			loop {
+				let event = receiver.recv()?;
+				if event.is_ok_and(|event| is_data_file_change(&event, &data_path)) {
+					break;
+				}
+			}
+			while receiver.recv_timeout(Duration::from_millis(100)).is_ok() {}
+
+			debug!("Reloading projects data");
+			self.data_lock.lock()?;
+			let load_result = load_projects(&self.data_file);
+			self.data_lock.release()?;
+			match load_result {
+				Ok(projects_data) => self.projects_data = projects_data,
+				Err(err) => println!("Error: could not reload {}: {err}", data_path.display()),
+			}
+		}
+	}
+
+	fn save(&mut self) -> Result<(), TaskManagerError> {
+		if self.projects_dirty {
+			storage::take_snapshot(&self.data_file, self.snapshots)?;
+		}
+		self.persist()?;
+		self.flush_audit();
+
+		Ok(())
+	}
+
+	fn is_dirty(&self) -> bool {
+		self.projects_dirty || self.config_dirty
+	}
+
+	fn audit(&mut self, entry: String) {
+		if !self.audit_log || self.dry_run {
+			return;
+		}
+
+		self.pending_audit.push(entry);
+	}
+
+	fn audit_project_command(&mut self, command: &ProjectCommand) {
+		if command.is_mutating() {
+			let ids: Vec<String> = self.audit_ids.iter().map(|id| id.to_string()).collect();
+			self.audit(format!("project {} {}", command.name(), ids.join(" ")).trim_end().to_string());
+		}
+	}
+
+	fn audit_config_command(&mut self, command: &ConfigCommand) {
+		match command {
+			ConfigCommand::Set { key, value } => self.audit(format!("config set {} {}", key, value)),
+			ConfigCommand::Reset { key: Some(key), .. } => self.audit(format!("config reset {}", key)),
+			ConfigCommand::Reset { key: None, .. } => self.audit("config reset".to_string()),
+			ConfigCommand::Get { .. } | ConfigCommand::Show => {},
+		}
+	}
+
+	fn flush_audit(&mut self) {
+		for entry in mem::take(&mut self.pending_audit) {
+			if let Err(err) = storage::append_audit_entry(&self.data_dir.join(storage::AUDIT_FILE), &entry) {
+				eprintln!("Warning: could not write {}: {}", storage::AUDIT_FILE, err);
+				break;
+			}
+		}
+	}
+
+	fn run_line(&mut self, namespace: &Namespace) -> Result<(), TaskManagerError> {
+		match namespace {
+			Namespace::Project(ProjectArgs { command: Some(ProjectCommand::Watch { .. }), .. }) => {
+				let message = "watch cannot be started from the REPL or a batch script";
+				Err(TaskManagerError::InvalidInput(message.to_string()))
+			},
+			Namespace::Project(ProjectArgs { command: Some(ProjectCommand::Undo), .. }) => {
+				let message = "undo cannot be run from the REPL or a batch script, run 'project undo' on its own";
+				Err(TaskManagerError::InvalidInput(message.to_string()))
+			},
+			Namespace::Project(args) => {
+				self.run_project_command(args)?;
+				if let Some(command) = &args.command {
+					self.audit_project_command(command);
+				}
+
+				Ok(())
+			},
+			Namespace::Config(args) => {
+				self.run_config_command(args)?;
+				if let Some(command) = &args.command {
+					self.audit_config_command(command);
+				}
+
+				Ok(())
+			},
+			Namespace::Repl | Namespace::Batch { .. } => {
+				let message = "repl and batch cannot be started from the REPL or a batch script";
+				Err(TaskManagerError::InvalidInput(message.to_string()))
+			},
+			Namespace::Completions { shell } => {
+				print_completions(*shell);
+
+				Ok(())
+			},
+		}
+	}
+
+	fn run_parsed_line(&mut self, cli: &Cli, matches: &ArgMatches) -> Result<(), TaskManagerError> {
+		let from_line = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+		let session_flags: Vec<String> = SESSION_FLAGS.into_iter()
+			.filter(|id| from_line(*id))
+			.map(|id| format!("--{}", id.replace('_', "-")))
+			.collect();
+		if !session_flags.is_empty() {
+			let message = format!("{} cannot be changed inside the REPL or a batch script", session_flags.join(", "));
+			return Err(TaskManagerError::InvalidInput(message));
+		}
+
+		let saved = (self.format, self.color, self.dry_run, self.quiet);
+		if from_line("format") {
+			self.format = cli.format;
+		}
+		if cli.no_color {
+			self.color = false;
+		}
+		if cli.quiet {
+			self.quiet = true;
+		}
+		if cli.dry_run {
+			self.dry_run = true;
+		}
+
+		let result = self.run_line(&cli.namespace);
+
+		(self.format, self.color, self.dry_run, self.quiet) = saved;
+
+		result
+	}
+
+	fn run_repl(&mut self) -> Result<(), TaskManagerError> {
+		let stdin = io::stdin();
+
+		loop {
+			print!("taskmanager> ");
+			io::stdout().flush()?;
+
+			let mut line = String::new();
+			if stdin.read_line(&mut line)? == 0 {
+				println!();
+				break;
+			}
+
+			match line.trim() {
+				"" => continue,
+				"exit" | "quit" => break,
+				"save" => {
+					if self.dry_run {
+						println!("Dry run: no changes were written");
+					} else if let Err(err) = self.save() {
+						println!("Error: {err}");
+					}
+					continue;
+				},
+				_ => {},
+			}
+
+			let words = match shlex::split(&line) {
+				Some(words) => words,
+				None => {
+					println!("Error: unbalanced quotes");
+					continue;
+				},
+			};
+			let (cli, matches) = match parse_line(words) {
+				Ok(parsed) => parsed,
+				Err(err) => {
+					let _ = err.print();
+					continue;
+				},
+			};
+
+			if let Err(err) = self.run_parsed_line(&cli, &matches) {
+				println!("Error: {err}");
+			}
+		}
+
+		if !self.dry_run {
+			self.save()?;
+		}
+
+		Ok(())
+	}
+
+	fn run_batch(&mut self, path: &Path, continue_on_error: bool) -> Result<(), TaskManagerError> {
+		let path = self.working_dir.join(path);
+		let contents = fs::read_to_string(&path)?;
+		let mut failed = 0;
+
+		for (index, line) in contents.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+
+			let line_result = match shlex::split(line) {
+				Some(words) => match parse_line(words) {
+					Ok((cli, matches)) => self.run_parsed_line(&cli, &matches),
+					Err(err) => Err(TaskManagerError::InvalidInput(err.to_string().trim_end().to_string())),
+				},
+				None => Err(TaskManagerError::InvalidInput("unbalanced quotes".to_string())),
+			};
+
+			match line_result {
+				Ok(_) => {},
+				Err(err) if continue_on_error => {
+					println!("Line {}: {}", index + 1, err);
+					failed += 1;
+				},
+				Err(err) => {
+					println!("Stopped at line {} of {}, no changes were written", index + 1, path.display());
+					return Err(err);
+				},
+			}
+		}
+
+		if failed > 0 {
+			println!("{} lines failed", failed);
+		}
+		if !self.dry_run {
+			self.save()?;
+		}
+
+		Ok(())
+	}
+
+	pub fn run(&mut self) -> Result<(), TaskManagerError> {
+		if !self.quiet {
+			eprintln!("Running taskmanager-cli lib version: {}", taskmanager::get_lib_version());
+		}
+		let run_result = self.run_namespace();
+		self.data_lock.release()?;
+
+		run_result
+	}
+
+	fn run_namespace(&mut self) -> Result<(), TaskManagerError> {
 		let namespace = self.namespace.clone();
+		debug!("Running {:?}", namespace);
 		match namespace {
 			Namespace::Project(args) => {
 				let run_result = self.run_project_command(&args);
 				match run_result {
 					Ok(_) => {
-						self.persist()?;
+						debug!("Command succeeded");
+						if let Some(command) = &args.command {
+							self.audit_project_command(command);
+						}
+						if self.dry_run {
+							return Ok(());
+						}
 
-						Ok(())
+						self.save()
 					},
 					Err(err) => Err(err),
 				}
@@ -257,13 +1915,238 @@ impl RuntimeConfig {
 				let run_result = self.run_config_command(&args);
 				match run_result {
 					Ok(_) => {
-						self.persist()?;
+						debug!("Command succeeded");
+						if self.dry_run {
+							return Ok(());
+						}
+						if let Some(command) = &args.command {
+							self.audit_config_command(command);
+						}
 
-						Ok(())
+						self.save()
 					},
 					Err(err) => Err(err),
 				}
-			}
+			},
+			Namespace::Repl => self.run_repl(),
+			Namespace::Batch { path, continue_on_error } => self.run_batch(&path, continue_on_error),
+			Namespace::Completions { shell } => {
+				print_completions(shell);
+
+				Ok(())
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::SystemTime;
+
+	fn ids() -> Vec<Uuid> {
+		vec![
+			Uuid::parse_str("3f2a1c4e-0000-4000-8000-000000000001").unwrap(),
+			Uuid::parse_str("3f2b9d10-0000-4000-8000-000000000002").unwrap(),
+			Uuid::parse_str("a1b2c3d4-0000-4000-8000-000000000003").unwrap(),
+		]
+	}
+
+	const GARBAGE: &[u8] = b"\x00\xff{\"projects\": [not json";
+
+	fn modified(path: &Path) -> SystemTime {
+		fs::metadata(path).unwrap().modified().unwrap()
+	}
+
+	fn runtime_in(dir: &Path, args: &[&str]) -> RuntimeConfig {
+		let mut argv = vec!["taskmanager", "--quiet", "--data-dir", dir.to_str().unwrap()];
+		argv.extend_from_slice(args);
+
+		RuntimeConfig::from_cli(Cli::try_parse_from(argv).unwrap()).unwrap()
+	}
+
+	fn run_in(dir: &Path, args: &[&str]) -> RuntimeConfig {
+		let mut runtime_config = runtime_in(dir, args);
+		runtime_config.run().unwrap();
+
+		runtime_config
+	}
+
+	#[test]
+	fn read_and_unchanged_commands_leave_files_untouched() {
+		let _env_lock = settings::ENV_LOCK.lock().unwrap();
+		let dir = tempfile::tempdir().unwrap();
+		let data_file = dir.path().join(storage::PROJECTS_FILE);
+		let config_file = dir.path().join(settings::CONFIG_FILE);
+		storage::write_json_atomic(&data_file, &project::ProjectData { projects: HashMap::new() }).unwrap();
+		settings::write_config(&config_file, &config::Config { persistence_mode: config::PersistenceMode::JSON }).unwrap();
+		let old = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+		for path in [&data_file, &config_file] {
+			File::options().write(true).open(path).unwrap().set_modified(old).unwrap();
+		}
+
+		let runtime_config = run_in(dir.path(), &["project", "list"]);
+		assert!(!runtime_config.is_dirty());
+		let runtime_config = run_in(dir.path(), &["config", "set", "persistence_mode", "JSON"]);
+		assert!(!runtime_config.is_dirty());
+		let runtime_config = run_in(dir.path(), &["config", "reset", "persistence_mode"]);
+		assert!(!runtime_config.is_dirty());
+
+		assert_eq!(modified(&data_file), old);
+		assert_eq!(modified(&config_file), old);
+		assert!(!dir.path().join("projects.json.bak.1").exists());
+	}
+
+	#[test]
+	fn by_name_is_rejected_without_a_project_id() {
+		let _env_lock = settings::ENV_LOCK.lock().unwrap();
+		let dir = tempfile::tempdir().unwrap();
+		settings::write_config(&dir.path().join(settings::CONFIG_FILE), &config::Config { persistence_mode: config::PersistenceMode::JSON }).unwrap();
+
+		for args in [&["project", "--by-name", "list"][..], &["project", "--by-name", "purge-completed", "--all-projects"]] {
+			let err = runtime_in(dir.path(), args).run().unwrap_err();
+			assert!(matches!(err, TaskManagerError::InvalidInput(_)));
+		}
+
+		run_in(dir.path(), &["project", "create", "Alpha", "First"]);
+		run_in(dir.path(), &["project", "--by-name", "show", "Alpha"]);
+		assert!(runtime_in(dir.path(), &["project", "--by-name", "show", "Beta"]).run().is_err());
+	}
+
+	#[test]
+	fn open_projects_reports_corrupt_file() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join(storage::PROJECTS_FILE);
+		fs::write(&path, GARBAGE).unwrap();
+
+		let err = open_projects(&path, false, false).unwrap_err();
+		assert!(matches!(err, TaskManagerError::Corrupt(..)));
+		assert!(err.to_string().starts_with(&format!("{} is corrupt (", path.display())));
+		assert!(err.to_string().contains("Run again with --force-reset"));
+		assert_eq!(fs::read(&path).unwrap(), GARBAGE);
+	}
+
+	#[test]
+	fn open_projects_force_reset_moves_corrupt_file_aside() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join(storage::PROJECTS_FILE);
+		fs::write(&path, GARBAGE).unwrap();
+
+		let projects_data = open_projects(&path, true, false).unwrap();
+		assert!(projects_data.projects.is_empty());
+		assert!(!path.exists());
+		assert_eq!(fs::read(dir.path().join("projects.json.corrupt")).unwrap(), GARBAGE);
+	}
+
+	#[test]
+	fn open_projects_force_reset_dry_run_leaves_file() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join(storage::PROJECTS_FILE);
+		fs::write(&path, GARBAGE).unwrap();
+
+		let projects_data = open_projects(&path, true, true).unwrap();
+		assert!(projects_data.projects.is_empty());
+		assert_eq!(fs::read(&path).unwrap(), GARBAGE);
+		assert!(!dir.path().join("projects.json.corrupt").exists());
+	}
+
+	#[test]
+	fn open_projects_starts_empty_without_file() {
+		let dir = tempfile::tempdir().unwrap();
+
+		let projects_data = open_projects(&dir.path().join(storage::PROJECTS_FILE), false, false).unwrap();
+		assert!(projects_data.projects.is_empty());
+	}
+
+	#[test]
+	fn parse_task_line_splits_name_and_description() {
+		assert_eq!(parse_task_line("Write docs").unwrap(), ("Write docs".to_string(), String::new()));
+		assert_eq!(
+			parse_task_line("  Write docs | Cover the import format  ").unwrap(),
+			("Write docs".to_string(), "Cover the import format".to_string())
+		);
+		assert_eq!(
+			parse_task_line("Review|a | b").unwrap(),
+			("Review".to_string(), "a | b".to_string())
+		);
+	}
+
+	#[test]
+	fn parse_task_line_rejects_empty_name() {
+		for line in ["   ", "| description only"] {
+			let err = parse_task_line(line).unwrap_err();
+			assert!(matches!(err, TaskManagerError::InvalidInput(_)));
+			assert_eq!(err.to_string(), "Task name must not be empty");
+		}
+	}
+
+	#[test]
+	fn page_args_apply_offset_and_limit() {
+		let items: Vec<u32> = (1..=5).collect();
+
+		assert_eq!(PageArgs::default().apply(items.clone()), vec![1, 2, 3, 4, 5]);
+		assert_eq!(PageArgs { limit: Some(2), offset: 0 }.apply(items.clone()), vec![1, 2]);
+		assert_eq!(PageArgs { limit: Some(2), offset: 3 }.apply(items.clone()), vec![4, 5]);
+		assert_eq!(PageArgs { limit: None, offset: 4 }.apply(items.clone()), vec![5]);
+		assert_eq!(PageArgs { limit: Some(2), offset: 10 }.apply(items.clone()), Vec::<u32>::new());
+		assert_eq!(PageArgs { limit: Some(0), offset: 0 }.apply(items), Vec::<u32>::new());
+	}
+
+	#[test]
+	fn name_contains_ignores_case() {
+		assert!(name_contains_ignore_case("Website Redesign", "website"));
+		assert!(name_contains_ignore_case("Website Redesign", "SITE re"));
+		assert!(name_contains_ignore_case("website redesign", "WebSite"));
+		assert!(name_contains_ignore_case("Überblick", "überb"));
+		assert!(!name_contains_ignore_case("Website Redesign", "mobile"));
+	}
+
+	#[test]
+	fn resolve_id_matches_unique_prefix() {
+		let ids = ids();
+
+		assert_eq!(resolve_id("Project", "a1b", ids.iter()).unwrap(), ids[2]);
+		assert_eq!(resolve_id("Project", "3F2B", ids.iter()).unwrap(), ids[1]);
+	}
+
+	#[test]
+	fn resolve_id_matches_full_id_with_or_without_hyphens() {
+		let ids = ids();
+
+		assert_eq!(resolve_id("Project", &ids[0].to_string(), ids.iter()).unwrap(), ids[0]);
+		assert_eq!(resolve_id("Project", &ids[0].simple().to_string(), ids.iter()).unwrap(), ids[0]);
+	}
+
+	#[test]
+	fn resolve_id_reports_ambiguous_prefix() {
+		let ids = ids();
+		let err = resolve_id("Project", "3f2", ids.iter()).unwrap_err();
+
+		assert!(matches!(err, TaskManagerError::InvalidInput(_)));
+		assert_eq!(
+			err.to_string(),
+			format!("Ambiguous project id '3f2' matches: {}, {}", ids[0], ids[1])
+		);
+	}
+
+	#[test]
+	fn resolve_id_reports_missing_id() {
+		let ids = ids();
+		let err = resolve_id("Task", "ffff", ids.iter()).unwrap_err();
+
+		assert!(matches!(err, TaskManagerError::NotFound(_)));
+		assert_eq!(err.to_string(), "Task not found");
+	}
+
+	#[test]
+	fn resolve_id_rejects_invalid_id() {
+		let ids = ids();
+		let too_long = "a".repeat(37);
+
+		for prefix in ["", "xyz", "3f2a!", too_long.as_str()] {
+			let err = resolve_id("Project", prefix, ids.iter()).unwrap_err();
+			assert!(matches!(err, TaskManagerError::InvalidId(_)));
+			assert_eq!(err.to_string(), format!("'{}' is not a valid project id", prefix));
 		}
 	}
 }
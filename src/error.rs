@@ -0,0 +1,100 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use crate::settings::ConfigError;
+
+#[derive(Debug)]
+pub enum TaskManagerError {
+	NotFound(String),
+	InvalidId(String),
+	InvalidInput(String),
+	Corrupt(PathBuf, serde_json::Error),
+	Library(String),
+	Io(io::Error),
+	Serde(serde_json::Error),
+	Config(ConfigError),
+	Csv(csv::Error),
+	Clap(clap::Error),
+	Watch(notify::Error),
+}
+
+impl fmt::Display for TaskManagerError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			TaskManagerError::NotFound(message) => write!(f, "{}", message),
+			TaskManagerError::InvalidId(message) => write!(f, "{}", message),
+			TaskManagerError::InvalidInput(message) => write!(f, "{}", message),
+			TaskManagerError::Corrupt(path, err) => write!(
+				f,
+				"{} is corrupt ({}). Run again with --force-reset to move it aside and start with an empty dataset",
+				path.display(), err
+			),
+			TaskManagerError::Library(message) => write!(f, "{}", message),
+			TaskManagerError::Io(err) => write!(f, "{}", err),
+			TaskManagerError::Serde(err) => write!(f, "{}", err),
+			TaskManagerError::Config(err) => write!(f, "{}", err),
+			TaskManagerError::Csv(err) => write!(f, "{}", err),
+			TaskManagerError::Clap(err) => write!(f, "{}", err.to_string().trim_end()),
+			TaskManagerError::Watch(err) => write!(f, "{}", err),
+		}
+	}
+}
+
+impl Error for TaskManagerError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			TaskManagerError::Corrupt(_, err) => Some(err),
+			TaskManagerError::Io(err) => Some(err),
+			TaskManagerError::Serde(err) => Some(err),
+			TaskManagerError::Config(err) => Some(err),
+			TaskManagerError::Csv(err) => Some(err),
+			TaskManagerError::Clap(err) => Some(err),
+			TaskManagerError::Watch(err) => Some(err),
+			_ => None,
+		}
+	}
+}
+
+impl From<io::Error> for TaskManagerError {
+	fn from(err: io::Error) -> TaskManagerError {
+		TaskManagerError::Io(err)
+	}
+}
+
+impl From<serde_json::Error> for TaskManagerError {
+	fn from(err: serde_json::Error) -> TaskManagerError {
+		TaskManagerError::Serde(err)
+	}
+}
+
+impl From<ConfigError> for TaskManagerError {
+	fn from(err: ConfigError) -> TaskManagerError {
+		TaskManagerError::Config(err)
+	}
+}
+
+impl From<csv::Error> for TaskManagerError {
+	fn from(err: csv::Error) -> TaskManagerError {
+		TaskManagerError::Csv(err)
+	}
+}
+
+impl From<clap::Error> for TaskManagerError {
+	fn from(err: clap::Error) -> TaskManagerError {
+		TaskManagerError::Clap(err)
+	}
+}
+
+impl From<notify::Error> for TaskManagerError {
+	fn from(err: notify::Error) -> TaskManagerError {
+		TaskManagerError::Watch(err)
+	}
+}
+
+impl From<mpsc::RecvError> for TaskManagerError {
+	fn from(err: mpsc::RecvError) -> TaskManagerError {
+		TaskManagerError::Io(io::Error::new(io::ErrorKind::BrokenPipe, err))
+	}
+}
@@ -0,0 +1,434 @@
+use std::collections::BTreeMap;
+use std::mem;
+use serde::Serialize;
+use uuid::Uuid;
+use taskmanager::project;
+
+#[derive(Debug, Default, Serialize)]
+pub struct StatusCounts {
+	pub todo: usize,
+	pub in_progress: usize,
+	pub complete: usize,
+}
+
+impl StatusCounts {
+	fn add(&mut self, status: &project::ProjectTaskStatus) {
+		if matches!(status, project::ProjectTaskStatus::Complete) {
+			self.complete += 1;
+		} else if matches!(status, project::ProjectTaskStatus::InProgress) {
+			self.in_progress += 1;
+		} else {
+			self.todo += 1;
+		}
+	}
+
+	fn merge(&mut self, other: &StatusCounts) {
+		self.todo += other.todo;
+		self.in_progress += other.in_progress;
+		self.complete += other.complete;
+	}
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct DatasetStats {
+	pub projects: usize,
+	pub tasks: usize,
+	pub by_status: StatusCounts,
+	pub by_type: BTreeMap<String, usize>,
+	pub completion_percent: f32,
+}
+
+fn percent(part: usize, total: usize) -> f32 {
+	if total == 0 {
+		return 0.0;
+	}
+
+	part as f32 * 100.0 / total as f32
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectStats {
+	pub id: Uuid,
+	pub name: String,
+	pub by_status: StatusCounts,
+}
+
+pub trait TaskExt {
+	fn set_status(&mut self, status: project::ProjectTaskStatus) -> bool;
+	fn rename(&mut self, name: &str) -> String;
+}
+
+impl TaskExt for project::ProjectTask {
+	fn set_status(&mut self, status: project::ProjectTaskStatus) -> bool {
+		if mem::discriminant(&self.status) == mem::discriminant(&status) {
+			return false;
+		}
+		self.status = status;
+
+		true
+	}
+
+	fn rename(&mut self, name: &str) -> String {
+		mem::replace(&mut self.name, name.to_string())
+	}
+}
+
+pub trait ProjectExt {
+	fn completion_stats(&self) -> (usize, usize);
+	fn completion_percent(&self) -> f32;
+	fn status_counts(&self) -> StatusCounts;
+	fn purge_completed(&mut self) -> usize;
+	fn count_with_status(&self, status: &project::ProjectTaskStatus) -> usize;
+	fn set_status_bulk(&mut self, from: &project::ProjectTaskStatus, to: &project::ProjectTaskStatus) -> usize;
+	fn rename(&mut self, name: &str) -> String;
+}
+
+impl ProjectExt for project::Project {
+	fn completion_stats(&self) -> (usize, usize) {
+		let complete = self.tasks.values()
+			.filter(|task| matches!(task.status, project::ProjectTaskStatus::Complete))
+			.count();
+
+		(complete, self.tasks.len())
+	}
+
+	fn completion_percent(&self) -> f32 {
+		let (complete, total) = self.completion_stats();
+
+		percent(complete, total)
+	}
+
+	fn status_counts(&self) -> StatusCounts {
+		let mut counts = StatusCounts::default();
+		for task in self.tasks.values() {
+			counts.add(&task.status);
+		}
+
+		counts
+	}
+
+	fn purge_completed(&mut self) -> usize {
+		let before = self.tasks.len();
+		self.tasks.retain(|_, task| !matches!(task.status, project::ProjectTaskStatus::Complete));
+
+		before - self.tasks.len()
+	}
+
+	fn count_with_status(&self, status: &project::ProjectTaskStatus) -> usize {
+		self.tasks.values()
+			.filter(|task| mem::discriminant(&task.status) == mem::discriminant(status))
+			.count()
+	}
+
+	fn set_status_bulk(&mut self, from: &project::ProjectTaskStatus, to: &project::ProjectTaskStatus) -> usize {
+		let mut changed = 0;
+		for task in self.tasks.values_mut() {
+			if mem::discriminant(&task.status) == mem::discriminant(from) && task.set_status(to.clone()) {
+				changed += 1;
+			}
+		}
+
+		changed
+	}
+
+	fn rename(&mut self, name: &str) -> String {
+		mem::replace(&mut self.name, name.to_string())
+	}
+}
+
+pub trait ProjectDataExt {
+	fn stats(&self) -> DatasetStats;
+	fn stats_by_project(&self) -> Vec<ProjectStats>;
+	fn find_projects_by_name(&self, name: &str) -> Vec<&project::Project>;
+	fn move_task(&mut self, task_id: &Uuid, from: &Uuid, to: &Uuid) -> bool;
+	fn merge_projects(&mut self, source: &Uuid, destination: &Uuid) -> Option<usize>;
+	fn clone_project(&mut self, project_id: &Uuid, name: &str) -> Option<Uuid>;
+}
+
+impl ProjectDataExt for project::ProjectData {
+	fn stats(&self) -> DatasetStats {
+		let mut stats = DatasetStats::default();
+		for project in self.get_projects() {
+			stats.projects += 1;
+			stats.tasks += project.tasks.len();
+			stats.by_status.merge(&project.status_counts());
+			for task in project.tasks.values() {
+				*stats.by_type.entry(format!("{:?}", task.type_)).or_insert(0) += 1;
+			}
+		}
+		stats.completion_percent = percent(stats.by_status.complete, stats.tasks);
+
+		stats
+	}
+
+	fn stats_by_project(&self) -> Vec<ProjectStats> {
+		let mut projects = self.get_projects();
+		projects.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+
+		projects.iter().map(|project| ProjectStats {
+			id: project.id,
+			name: project.name.clone(),
+			by_status: project.status_counts(),
+		}).collect()
+	}
+
+	fn find_projects_by_name(&self, name: &str) -> Vec<&project::Project> {
+		let mut projects: Vec<&project::Project> = self.get_projects().into_iter()
+			.filter(|project| project.name == name)
+			.collect();
+		projects.sort_by(|a, b| a.id.cmp(&b.id));
+
+		projects
+	}
+	fn move_task(&mut self, task_id: &Uuid, from: &Uuid, to: &Uuid) -> bool {
+		if from == to || !self.projects.contains_key(to) {
+			return false;
+		}
+		let task = match self.projects.get_mut(from).and_then(|project| project.tasks.remove(task_id)) {
+			Some(task) => task,
+			None => return false,
+		};
+		if let Some(project) = self.projects.get_mut(to) {
+			project.tasks.insert(*task_id, task);
+		}
+
+		true
+	}
+
+	fn merge_projects(&mut self, source: &Uuid, destination: &Uuid) -> Option<usize> {
+		if source == destination || !self.projects.contains_key(destination) {
+			return None;
+		}
+		let source_project = self.projects.remove(source)?;
+		let destination_project = self.projects.get_mut(destination)?;
+		let task_count = source_project.tasks.len();
+		for (task_id, task) in source_project.tasks {
+			if destination_project.tasks.contains_key(&task_id) {
+				destination_project.tasks.insert(Uuid::new_v4(), task);
+			} else {
+				destination_project.tasks.insert(task_id, task);
+			}
+		}
+
+		Some(task_count)
+	}
+
+	fn clone_project(&mut self, project_id: &Uuid, name: &str) -> Option<Uuid> {
+		let source = self.projects.get(project_id)?.clone();
+		let clone_id = self.create_project(name, &source.description);
+		let clone = self.projects.get_mut(&clone_id)?;
+		let mut tasks: Vec<(&Uuid, &project::ProjectTask)> = source.tasks.iter().collect();
+		tasks.sort_by(|a, b| a.1.name.cmp(&b.1.name).then(a.0.cmp(b.0)));
+		for (_, task) in tasks {
+			let task_id = clone.create_task(&task.name, &task.description);
+			if let Some(cloned_task) = clone.tasks.get_mut(&task_id) {
+				cloned_task.type_ = task.type_.clone();
+				cloned_task.set_status(project::ProjectTaskStatus::Todo);
+			}
+		}
+
+		Some(clone_id)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashMap;
+
+	fn empty_data() -> project::ProjectData {
+		project::ProjectData { projects: HashMap::new() }
+	}
+
+	fn add_project(data: &mut project::ProjectData, name: &str, statuses: &[project::ProjectTaskStatus]) -> Uuid {
+		let project_id = data.create_project(name, "");
+		let project = data.get_project_mut(&project_id).unwrap();
+		for (index, status) in statuses.iter().enumerate() {
+			let task_id = project.create_task(&format!("Task {}", index), "");
+			project.tasks.get_mut(&task_id).unwrap().set_status(status.clone());
+		}
+
+		project_id
+	}
+
+	fn mixed_statuses() -> Vec<project::ProjectTaskStatus> {
+		vec![
+			project::ProjectTaskStatus::Todo,
+			project::ProjectTaskStatus::InProgress,
+			project::ProjectTaskStatus::InProgress,
+			project::ProjectTaskStatus::Complete,
+		]
+	}
+
+	#[test]
+	fn set_status_reports_real_changes_only() {
+		let mut data = empty_data();
+		let project_id = add_project(&mut data, "Alpha", &[project::ProjectTaskStatus::Todo]);
+		let task = data.get_project_mut(&project_id).unwrap().tasks.values_mut().next().unwrap();
+
+		assert!(!task.set_status(project::ProjectTaskStatus::Todo));
+		assert!(task.set_status(project::ProjectTaskStatus::Complete));
+		assert!(matches!(task.status, project::ProjectTaskStatus::Complete));
+		assert!(!task.set_status(project::ProjectTaskStatus::Complete));
+	}
+
+	#[test]
+	fn completion_stats_and_percent() {
+		let mut data = empty_data();
+		let alpha = add_project(&mut data, "Alpha", &mixed_statuses());
+		let empty = add_project(&mut data, "Empty", &[]);
+
+		let alpha = data.get_project(&alpha).unwrap();
+		assert_eq!(alpha.completion_stats(), (1, 4));
+		assert_eq!(alpha.completion_percent(), 25.0);
+		let empty = data.get_project(&empty).unwrap();
+		assert_eq!(empty.completion_stats(), (0, 0));
+		assert_eq!(empty.completion_percent(), 0.0);
+	}
+
+	#[test]
+	fn status_counts_groups_every_task() {
+		let mut data = empty_data();
+		let alpha = add_project(&mut data, "Alpha", &mixed_statuses());
+
+		let counts = data.get_project(&alpha).unwrap().status_counts();
+		assert_eq!((counts.todo, counts.in_progress, counts.complete), (1, 2, 1));
+	}
+
+	#[test]
+	fn purge_completed_removes_only_complete_tasks() {
+		let mut data = empty_data();
+		let alpha = add_project(&mut data, "Alpha", &mixed_statuses());
+		let project = data.get_project_mut(&alpha).unwrap();
+
+		assert_eq!(project.purge_completed(), 1);
+		assert_eq!(project.tasks.len(), 3);
+		assert_eq!(project.count_with_status(&project::ProjectTaskStatus::Complete), 0);
+		assert_eq!(project.purge_completed(), 0);
+	}
+
+	#[test]
+	fn set_status_bulk_changes_matching_tasks() {
+		let mut data = empty_data();
+		let alpha = add_project(&mut data, "Alpha", &mixed_statuses());
+		let project = data.get_project_mut(&alpha).unwrap();
+
+		assert_eq!(project.count_with_status(&project::ProjectTaskStatus::InProgress), 2);
+		let changed = project.set_status_bulk(&project::ProjectTaskStatus::InProgress, &project::ProjectTaskStatus::Complete);
+		assert_eq!(changed, 2);
+		assert_eq!(project.count_with_status(&project::ProjectTaskStatus::InProgress), 0);
+		assert_eq!(project.count_with_status(&project::ProjectTaskStatus::Complete), 3);
+		let unchanged = project.set_status_bulk(&project::ProjectTaskStatus::Todo, &project::ProjectTaskStatus::Todo);
+		assert_eq!(unchanged, 0);
+	}
+
+	#[test]
+	fn stats_sums_every_project() {
+		let mut data = empty_data();
+		add_project(&mut data, "Alpha", &mixed_statuses());
+		add_project(&mut data, "Beta", &[project::ProjectTaskStatus::Complete]);
+
+		let stats = data.stats();
+		assert_eq!(stats.projects, 2);
+		assert_eq!(stats.tasks, 5);
+		assert_eq!((stats.by_status.todo, stats.by_status.in_progress, stats.by_status.complete), (1, 2, 2));
+		assert_eq!(stats.by_type.values().sum::<usize>(), 5);
+		assert_eq!(stats.completion_percent, 40.0);
+
+		let by_project = data.stats_by_project();
+		let names: Vec<&str> = by_project.iter().map(|project| project.name.as_str()).collect();
+		assert_eq!(names, vec!["Alpha", "Beta"]);
+	}
+
+	#[test]
+	fn find_projects_by_name_matches_exact_names() {
+		let mut data = empty_data();
+		let first = add_project(&mut data, "Alpha", &[]);
+		let second = add_project(&mut data, "Alpha", &[]);
+		add_project(&mut data, "Alphabet", &[]);
+
+		let mut expected = vec![first, second];
+		expected.sort();
+		let found: Vec<Uuid> = data.find_projects_by_name("Alpha").iter().map(|project| project.id).collect();
+		assert_eq!(found, expected);
+		assert!(data.find_projects_by_name("alpha").is_empty());
+	}
+
+	#[test]
+	fn move_task_round_trips_between_projects() {
+		let mut data = empty_data();
+		let alpha = data.create_project("Alpha", "First");
+		let beta = data.create_project("Beta", "Second");
+		let task = data.get_project_mut(&alpha).unwrap().create_task("Write", "Docs");
+
+		assert!(data.move_task(&task, &alpha, &beta));
+		assert!(data.get_project(&alpha).unwrap().tasks.is_empty());
+		assert_eq!(data.get_project(&beta).unwrap().tasks[&task].name, "Write");
+
+		assert!(!data.move_task(&task, &alpha, &beta));
+		assert!(!data.move_task(&task, &beta, &beta));
+		assert!(data.move_task(&task, &beta, &alpha));
+		assert!(data.get_project(&beta).unwrap().tasks.is_empty());
+		assert_eq!(data.get_project(&alpha).unwrap().tasks[&task].description, "Docs");
+	}
+	#[test]
+	fn merge_projects_moves_every_task_and_removes_source() {
+		let mut data = empty_data();
+		let alpha = data.create_project("Alpha", "First");
+		let beta = data.create_project("Beta", "Second");
+		for name in ["One", "Two", "Three"] {
+			data.get_project_mut(&alpha).unwrap().create_task(name, "");
+		}
+		data.get_project_mut(&beta).unwrap().create_task("Four", "");
+
+		assert_eq!(data.merge_projects(&alpha, &alpha), None);
+		assert_eq!(data.merge_projects(&alpha, &beta), Some(3));
+		assert!(data.get_project(&alpha).is_none());
+		assert_eq!(data.get_project(&beta).unwrap().tasks.len(), 4);
+		assert_eq!(data.projects.len(), 1);
+		assert_eq!(data.merge_projects(&alpha, &beta), None);
+	}
+	#[test]
+	fn rename_keeps_description() {
+		let mut data = empty_data();
+		let alpha = data.create_project("Alpha", "First project");
+		let project = data.get_project_mut(&alpha).unwrap();
+		let task = project.create_task("Write", "Docs");
+
+		assert_eq!(project.rename("Gamma"), "Alpha");
+		assert_eq!(project.name, "Gamma");
+		assert_eq!(project.description, "First project");
+
+		let task = project.tasks.get_mut(&task).unwrap();
+		assert_eq!(task.rename("Review"), "Write");
+		assert_eq!(task.name, "Review");
+		assert_eq!(task.description, "Docs");
+	}
+	#[test]
+	fn clone_project_gets_fresh_ids_and_todo_tasks() {
+		let mut data = empty_data();
+		let alpha = data.create_project("Alpha", "Template");
+		let project = data.get_project_mut(&alpha).unwrap();
+		let statuses = [
+			project::ProjectTaskStatus::Todo,
+			project::ProjectTaskStatus::InProgress,
+			project::ProjectTaskStatus::Complete,
+		];
+		for (name, status) in ["One", "Two", "Three"].into_iter().zip(statuses) {
+			let task = project.create_task(name, "");
+			project.tasks.get_mut(&task).unwrap().set_status(status);
+		}
+
+		let clone = data.clone_project(&alpha, "Beta").unwrap();
+		assert_ne!(clone, alpha);
+		let source = data.get_project(&alpha).unwrap();
+		let cloned = data.get_project(&clone).unwrap();
+		assert_eq!(cloned.name, "Beta");
+		assert_eq!(cloned.description, "Template");
+		assert_eq!(cloned.tasks.len(), 3);
+		assert!(cloned.tasks.keys().all(|task_id| !source.tasks.contains_key(task_id)));
+		assert_eq!(cloned.count_with_status(&project::ProjectTaskStatus::Todo), 3);
+		assert_eq!(source.count_with_status(&project::ProjectTaskStatus::Complete), 1);
+		assert_eq!(data.clone_project(&Uuid::new_v4(), "Gamma"), None);
+	}
+}
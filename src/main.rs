@@ -1,15 +1,69 @@
+use std::io;
 use std::process;
+use error::TaskManagerError;
 
 mod cli;
+mod settings;
+mod render;
+mod storage;
+mod ext;
+mod error;
+
+const EXIT_NOT_FOUND: i32 = 2;
+const EXIT_INVALID_ARGUMENT: i32 = 3;
+const EXIT_IO: i32 = 4;
+
+fn exit_code(err: &TaskManagerError) -> i32 {
+	match err {
+		TaskManagerError::NotFound(_) => EXIT_NOT_FOUND,
+		TaskManagerError::InvalidId(_) | TaskManagerError::InvalidInput(_) | TaskManagerError::Config(_) => EXIT_INVALID_ARGUMENT,
+		TaskManagerError::Corrupt(..) | TaskManagerError::Watch(_) => EXIT_IO,
+		TaskManagerError::Library(_) => 1,
+		TaskManagerError::Io(err) => match err.kind() {
+			io::ErrorKind::NotFound => EXIT_NOT_FOUND,
+			io::ErrorKind::InvalidInput | io::ErrorKind::AlreadyExists => EXIT_INVALID_ARGUMENT,
+			_ => EXIT_IO,
+		},
+		TaskManagerError::Serde(err) => if err.is_io() { EXIT_IO } else { EXIT_INVALID_ARGUMENT },
+		TaskManagerError::Csv(err) => if err.is_io_error() { EXIT_IO } else { EXIT_INVALID_ARGUMENT },
+		TaskManagerError::Clap(err) => if err.use_stderr() { EXIT_INVALID_ARGUMENT } else { 0 },
+	}
+}
 
 fn main() {
 	let mut rtc = cli::RuntimeConfig::build().unwrap_or_else(|err| {
-		println!("Runtime config error: {err}");
-		process::exit(1);
+		let code = exit_code(&err);
+		if let TaskManagerError::Clap(err) = &err {
+			let _ = err.print();
+			process::exit(code);
+		}
+		println!("Runtime config error: {err} (exit code {code})");
+		process::exit(code);
 	});
 
 	rtc.run().unwrap_or_else(|err| {
-        println!("Runtime error: {err}");
-        process::exit(1);
+		let code = exit_code(&err);
+        println!("Runtime error: {err} (exit code {code})");
+        process::exit(code);
     });
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn exit_code_follows_error_kind() {
+		assert_eq!(exit_code(&TaskManagerError::NotFound("Project not found".to_string())), EXIT_NOT_FOUND);
+		assert_eq!(exit_code(&TaskManagerError::InvalidId("bad".to_string())), EXIT_INVALID_ARGUMENT);
+		assert_eq!(exit_code(&TaskManagerError::from(settings::ConfigError::UnknownKey("missing".to_string()))), EXIT_INVALID_ARGUMENT);
+		assert_eq!(exit_code(&TaskManagerError::from(io::Error::from(io::ErrorKind::NotFound))), EXIT_NOT_FOUND);
+		assert_eq!(exit_code(&TaskManagerError::from(io::Error::from(io::ErrorKind::PermissionDenied))), EXIT_IO);
+		let serde_err = serde_json::from_str::<u32>("not json").unwrap_err();
+		assert_eq!(exit_code(&TaskManagerError::from(serde_err)), EXIT_INVALID_ARGUMENT);
+		let serde_err = serde_json::from_str::<u32>("not json").unwrap_err();
+		assert_eq!(exit_code(&TaskManagerError::Corrupt("projects.json".into(), serde_err)), EXIT_IO);
+		assert_eq!(exit_code(&TaskManagerError::from(clap::Error::new(clap::error::ErrorKind::DisplayHelp))), 0);
+		assert_eq!(exit_code(&TaskManagerError::from(clap::Error::new(clap::error::ErrorKind::UnknownArgument))), EXIT_INVALID_ARGUMENT);
+	}
+}
@@ -0,0 +1,372 @@
+use std::fmt::Write;
+use std::io;
+use std::path::PathBuf;
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use uuid::Uuid;
+use taskmanager::project;
+use crate::ext::{DatasetStats, ProjectExt, ProjectStats};
+
+#[derive(Debug, Serialize)]
+pub struct ProjectView {
+	pub id: Uuid,
+	pub name: String,
+	pub description: String,
+	pub tasks_complete: usize,
+	pub tasks_total: usize,
+	pub completion_percent: f32,
+}
+
+impl ProjectView {
+	pub fn new(project: &project::Project) -> ProjectView {
+		let (tasks_complete, tasks_total) = project.completion_stats();
+
+		ProjectView {
+			id: project.id,
+			name: project.name.clone(),
+			description: project.description.clone(),
+			tasks_complete,
+			tasks_total,
+			completion_percent: project.completion_percent(),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatusKind {
+	Todo,
+	InProgress,
+	Complete,
+}
+
+const STATUS_GROUPS: [StatusKind; 3] = [StatusKind::Todo, StatusKind::InProgress, StatusKind::Complete];
+
+impl StatusKind {
+	fn new(status: &project::ProjectTaskStatus) -> StatusKind {
+		if matches!(status, project::ProjectTaskStatus::Complete) {
+			StatusKind::Complete
+		} else if matches!(status, project::ProjectTaskStatus::InProgress) {
+			StatusKind::InProgress
+		} else {
+			StatusKind::Todo
+		}
+	}
+
+	fn symbol(&self) -> &'static str {
+		match self {
+			StatusKind::Todo => "[ ]",
+			StatusKind::InProgress => "[~]",
+			StatusKind::Complete => "[x]",
+		}
+	}
+
+	fn label(&self) -> &'static str {
+		match self {
+			StatusKind::Todo => "Todo",
+			StatusKind::InProgress => "In Progress",
+			StatusKind::Complete => "Complete",
+		}
+	}
+
+	fn paint(&self, line: &str) -> String {
+		match self {
+			StatusKind::Todo => line.to_string(),
+			StatusKind::InProgress => line.yellow().to_string(),
+			StatusKind::Complete => line.green().to_string(),
+		}
+	}
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskView {
+	pub id: Uuid,
+	pub name: String,
+	pub description: String,
+	pub status: String,
+	#[serde(rename = "type")]
+	pub type_: String,
+	#[serde(skip)]
+	pub kind: StatusKind,
+}
+
+impl TaskView {
+	pub fn new(task_id: &Uuid, task: &project::ProjectTask) -> TaskView {
+		TaskView {
+			id: *task_id,
+			name: task.name.clone(),
+			description: task.description.clone(),
+			status: format!("{:?}", task.status),
+			type_: format!("{:?}", task.type_),
+			kind: StatusKind::new(&task.status),
+		}
+	}
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskGroupView {
+	pub status: &'static str,
+	pub tasks: Vec<TaskView>,
+}
+
+pub fn group_by_status(tasks: Vec<TaskView>) -> Vec<TaskGroupView> {
+	let mut groups: Vec<TaskGroupView> = STATUS_GROUPS.iter()
+		.map(|kind| TaskGroupView { status: kind.label(), tasks: Vec::new() })
+		.collect();
+	for task in tasks {
+		if let Some(index) = STATUS_GROUPS.iter().position(|kind| *kind == task.kind) {
+			groups[index].tasks.push(task);
+		}
+	}
+	groups.retain(|group| !group.tasks.is_empty());
+
+	groups
+}
+
+pub fn tasks_csv<W: io::Write>(writer: W, rows: &[(&str, TaskView)]) -> Result<(), csv::Error> {
+	let mut writer = csv::Writer::from_writer(writer);
+	writer.write_record(["id", "project", "name", "description", "status", "type"])?;
+	for (project_name, view) in rows {
+		writer.write_record([
+			view.id.to_string().as_str(),
+			*project_name,
+			view.name.as_str(),
+			view.description.as_str(),
+			view.status.as_str(),
+			view.type_.as_str(),
+		])?;
+	}
+	writer.flush()?;
+
+	Ok(())
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectTasksView {
+	pub id: Uuid,
+	pub name: String,
+	pub tasks: Vec<TaskView>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InfoView {
+	pub version: String,
+	pub lib_version: String,
+	pub data_dir: PathBuf,
+	pub data_file: PathBuf,
+	pub data_file_exists: bool,
+	pub config_file: PathBuf,
+	pub config_file_exists: bool,
+	pub projects: usize,
+	pub tasks: usize,
+}
+
+pub fn project_lines(projects: &[ProjectView]) -> String {
+	let mut output = String::new();
+	for project in projects {
+		let _ = writeln!(
+			output,
+			"{}: {} - {} ({}/{} complete, {:.0}%)",
+			project.id, project.name, project.description, project.tasks_complete, project.tasks_total, project.completion_percent
+		);
+	}
+
+	output
+}
+
+pub fn project_detail(project: &ProjectView) -> String {
+	let mut output = String::new();
+	let _ = writeln!(output, "Name:        {}", project.name);
+	let _ = writeln!(output, "Id:          {}", project.id);
+	let _ = writeln!(output, "Description: {}", project.description);
+	let _ = writeln!(output, "Tasks:       {} ({} complete, {:.0}%)", project.tasks_total, project.tasks_complete, project.completion_percent);
+
+	output
+}
+
+pub fn dataset_stats(stats: &DatasetStats) -> String {
+	let mut output = String::new();
+	let _ = writeln!(output, "Projects: {}", stats.projects);
+	let _ = writeln!(output, "Tasks:    {}", stats.tasks);
+	let _ = writeln!(output, "By status:");
+	let _ = writeln!(output, "  todo:        {}", stats.by_status.todo);
+	let _ = writeln!(output, "  in_progress: {}", stats.by_status.in_progress);
+	let _ = writeln!(output, "  complete:    {}", stats.by_status.complete);
+	let _ = writeln!(output, "Completion: {:.0}%", stats.completion_percent);
+	let _ = writeln!(output, "By type:");
+	for (type_, count) in &stats.by_type {
+		let _ = writeln!(output, "  {}: {}", type_, count);
+	}
+
+	output
+}
+
+pub fn info(info: &InfoView) -> String {
+	let existence = |exists: bool| if exists { "exists" } else { "missing" };
+
+	let mut output = String::new();
+	let _ = writeln!(output, "Version:     {}", info.version);
+	let _ = writeln!(output, "Lib version: {}", info.lib_version);
+	let _ = writeln!(output, "Data dir:    {}", info.data_dir.display());
+	let _ = writeln!(output, "Data file:   {} ({})", info.data_file.display(), existence(info.data_file_exists));
+	let _ = writeln!(output, "Config file: {} ({})", info.config_file.display(), existence(info.config_file_exists));
+	let _ = writeln!(output, "Projects:    {}", info.projects);
+	let _ = writeln!(output, "Tasks:       {}", info.tasks);
+
+	output
+}
+
+pub fn project_stats_table(projects: &[ProjectStats]) -> String {
+	let mut rows: Vec<[String; 5]> = vec![[
+		"PROJECT".to_string(),
+		"ID".to_string(),
+		"TODO".to_string(),
+		"IN_PROGRESS".to_string(),
+		"COMPLETE".to_string(),
+	]];
+	for project in projects {
+		rows.push([
+			project.name.clone(),
+			project.id.to_string(),
+			project.by_status.todo.to_string(),
+			project.by_status.in_progress.to_string(),
+			project.by_status.complete.to_string(),
+		]);
+	}
+
+	let mut output = String::new();
+	for line in table(&rows) {
+		output.push_str(&line);
+		output.push('\n');
+	}
+
+	output
+}
+
+pub fn task_detail(task: &TaskView, project: &project::Project) -> String {
+	let mut output = String::new();
+	let _ = writeln!(output, "Name:        {}", task.name);
+	let _ = writeln!(output, "Id:          {}", task.id);
+	let _ = writeln!(output, "Project:     {} ({})", project.name, project.id);
+	let _ = writeln!(output, "Status:      {}", task.status);
+	let _ = writeln!(output, "Type:        {}", task.type_);
+	let _ = writeln!(output, "Description: {}", task.description);
+
+	output
+}
+
+pub fn task_table(tasks: &[TaskView], color: bool) -> String {
+	let rows: Vec<[String; 6]> = tasks.iter().map(|task| {
+		[
+			task.kind.symbol().to_string(),
+			task.id.to_string(),
+			task.name.clone(),
+			format!("[{}]", task.status),
+			task.type_.clone(),
+			task.description.clone(),
+		]
+	}).collect();
+
+	let mut output = String::new();
+	for (task, line) in tasks.iter().zip(table(&rows)) {
+		if color {
+			output.push_str(&task.kind.paint(&line));
+		} else {
+			output.push_str(&line);
+		}
+		output.push('\n');
+	}
+
+	output
+}
+
+pub fn grouped_task_table(groups: &[TaskGroupView], color: bool) -> String {
+	let mut output = String::new();
+	for group in groups {
+		let _ = writeln!(output, "{} ({}):", group.status, group.tasks.len());
+		for line in task_table(&group.tasks, color).lines() {
+			let _ = writeln!(output, "  {}", line);
+		}
+	}
+
+	output
+}
+
+fn table<const N: usize>(rows: &[[String; N]]) -> Vec<String> {
+	let mut widths = [0; N];
+	for row in rows {
+		for (column, cell) in row.iter().enumerate() {
+			widths[column] = widths[column].max(cell.chars().count());
+		}
+	}
+
+	rows.iter().map(|row| {
+		let mut line = String::new();
+		for (column, cell) in row.iter().enumerate() {
+			if column + 1 == N {
+				line.push_str(cell);
+			} else {
+				let _ = write!(line, "{:<width$}  ", cell, width = widths[column]);
+			}
+		}
+
+		line.trim_end().to_string()
+	}).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn task_view(name: &str, description: &str, kind: StatusKind) -> TaskView {
+		TaskView {
+			id: Uuid::nil(),
+			name: name.to_string(),
+			description: description.to_string(),
+			status: format!("{:?}", kind),
+			type_: "Task".to_string(),
+			kind,
+		}
+	}
+
+	#[test]
+	fn group_by_status_orders_groups_and_skips_empty() {
+		let tasks = vec![
+			task_view("a", "", StatusKind::Complete),
+			task_view("b", "", StatusKind::Todo),
+			task_view("c", "", StatusKind::Complete),
+		];
+
+		let groups = group_by_status(tasks);
+		let summary: Vec<(&str, Vec<&str>)> = groups.iter()
+			.map(|group| (group.status, group.tasks.iter().map(|task| task.name.as_str()).collect()))
+			.collect();
+
+		assert_eq!(summary, vec![
+			(StatusKind::Todo.label(), vec!["b"]),
+			(StatusKind::Complete.label(), vec!["a", "c"]),
+		]);
+	}
+
+	#[test]
+	fn group_by_status_of_no_tasks_is_empty() {
+		assert!(group_by_status(Vec::new()).is_empty());
+	}
+
+	#[test]
+	fn tasks_csv_quotes_commas_and_quotes() {
+		let rows = vec![("Launch, phase 1", task_view("Fix \"login\" page", "line one\nline two", StatusKind::Todo))];
+		let mut output = Vec::new();
+		tasks_csv(&mut output, &rows).unwrap();
+
+		let output = String::from_utf8(output).unwrap();
+		assert_eq!(output.lines().next(), Some("id,project,name,description,status,type"));
+		assert!(output.contains("\"Launch, phase 1\",\"Fix \"\"login\"\" page\""));
+
+		let mut reader = csv::Reader::from_reader(output.as_bytes());
+		let records: Vec<csv::StringRecord> = reader.records().collect::<Result<_, _>>().unwrap();
+		assert_eq!(records.len(), 1);
+		assert_eq!(&records[0][1], "Launch, phase 1");
+		assert_eq!(&records[0][2], "Fix \"login\" page");
+		assert_eq!(&records[0][3], "line one\nline two");
+	}
+}
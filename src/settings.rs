@@ -0,0 +1,194 @@
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+use taskmanager::config;
+use crate::storage;
+use crate::error::TaskManagerError;
+
+pub const CONFIG_FILE: &str = "config.json";
+
+#[cfg(test)]
+pub static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+#[derive(Debug)]
+pub enum ConfigError {
+	UnknownKey(String),
+	InvalidValue {
+		key: String,
+		value: String,
+		allowed: &'static [&'static str],
+	},
+}
+
+impl fmt::Display for ConfigError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ConfigError::UnknownKey(key) => write!(f, "Invalid config key: {}", key),
+			ConfigError::InvalidValue { key, value, allowed } => {
+				write!(f, "Invalid value for {}: {} (allowed values: {})", key, value, allowed.join(", "))
+			},
+		}
+	}
+}
+
+impl Error for ConfigError {}
+
+struct ConfigKey {
+	name: &'static str,
+	allowed: &'static [&'static str],
+	default: &'static str,
+	get: fn(&config::Config) -> String,
+	set: fn(&mut config::Config, &str) -> bool,
+}
+
+const KEYS: &[ConfigKey] = &[
+	ConfigKey {
+		name: "persistence_mode",
+		allowed: &["json"],
+		default: "json",
+		get: |config| format!("{:?}", config.persistence_mode).to_lowercase(),
+		set: |config, value| match value {
+			"json" => {
+				config.persistence_mode = config::PersistenceMode::JSON;
+				true
+			},
+			_ => false,
+		},
+	},
+];
+
+fn find_key(key: &str) -> Result<&'static ConfigKey, ConfigError> {
+	KEYS.iter().find(|entry| entry.name == key).ok_or_else(|| ConfigError::UnknownKey(key.to_string()))
+}
+
+pub trait ConfigExt {
+	fn get(&self, key: &str) -> Option<String>;
+	fn set(&mut self, key: &str, value: &str) -> Result<(), ConfigError>;
+	fn entries(&self) -> Vec<(&'static str, String)>;
+	fn reset(&mut self, key: &str) -> Result<(), ConfigError>;
+	fn reset_all(&mut self);
+	fn with_env_overrides(&self) -> Result<config::Config, ConfigError>;
+}
+
+impl ConfigExt for config::Config {
+	fn get(&self, key: &str) -> Option<String> {
+		find_key(key).ok().map(|entry| (entry.get)(self))
+	}
+
+	fn set(&mut self, key: &str, value: &str) -> Result<(), ConfigError> {
+		let entry = find_key(key)?;
+		if (entry.set)(self, value.to_lowercase().as_str()) {
+			Ok(())
+		} else {
+			Err(ConfigError::InvalidValue { key: key.to_string(), value: value.to_string(), allowed: entry.allowed })
+		}
+	}
+
+	fn entries(&self) -> Vec<(&'static str, String)> {
+		KEYS.iter().map(|entry| (entry.name, (entry.get)(self))).collect()
+	}
+
+	fn reset(&mut self, key: &str) -> Result<(), ConfigError> {
+		let entry = find_key(key)?;
+		(entry.set)(self, entry.default);
+
+		Ok(())
+	}
+
+	fn reset_all(&mut self) {
+		for entry in KEYS {
+			(entry.set)(self, entry.default);
+		}
+	}
+
+	fn with_env_overrides(&self) -> Result<config::Config, ConfigError> {
+		let mut config = self.clone();
+		for entry in KEYS {
+			if let Ok(value) = env::var(env_var_name(entry.name)) {
+				config.set(entry.name, &value)?;
+			}
+		}
+
+		Ok(config)
+	}
+}
+
+pub fn env_var_name(key: &str) -> String {
+	format!("TASKMANAGER_{}", key.to_uppercase())
+}
+
+pub fn write_config(path: &Path, config: &config::Config) -> Result<(), TaskManagerError> {
+	storage::write_json_atomic(path, config)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::ffi::OsString;
+
+	struct EnvGuard {
+		name: String,
+		previous: Option<OsString>,
+	}
+
+	impl EnvGuard {
+		fn set(name: String, value: &str) -> EnvGuard {
+			let previous = env::var_os(&name);
+			env::set_var(&name, value);
+
+			EnvGuard { name, previous }
+		}
+	}
+
+	impl Drop for EnvGuard {
+		fn drop(&mut self) {
+			match &self.previous {
+				Some(value) => env::set_var(&self.name, value),
+				None => env::remove_var(&self.name),
+			}
+		}
+	}
+
+	fn default_config() -> config::Config {
+		config::Config { persistence_mode: config::PersistenceMode::JSON }
+	}
+
+	#[test]
+	fn reset_restores_registry_defaults() {
+		let mut config = default_config();
+		config.set("persistence_mode", "JSON").unwrap();
+
+		config.reset("persistence_mode").unwrap();
+		assert_eq!(config.get("persistence_mode").as_deref(), Some("json"));
+		assert!(matches!(config.reset("missing"), Err(ConfigError::UnknownKey(_))));
+
+		config.reset_all();
+		let defaults: Vec<(&str, String)> = KEYS.iter().map(|entry| (entry.name, entry.default.to_string())).collect();
+		assert_eq!(config.entries(), defaults);
+	}
+	#[test]
+	fn env_overrides_apply_and_are_validated() {
+		let _env_lock = ENV_LOCK.lock().unwrap();
+		let config = default_config();
+		let name = env_var_name("persistence_mode");
+		assert_eq!(name, "TASKMANAGER_PERSISTENCE_MODE");
+
+		{
+			let _guard = EnvGuard::set(name.clone(), "JSON");
+			let effective = config.with_env_overrides().unwrap();
+			assert_eq!(effective.get("persistence_mode").as_deref(), Some("json"));
+		}
+		{
+			let _guard = EnvGuard::set(name.clone(), "yaml");
+			match config.with_env_overrides() {
+				Err(ConfigError::InvalidValue { key, value, .. }) => {
+					assert_eq!(key, "persistence_mode");
+					assert_eq!(value, "yaml");
+				},
+				other => panic!("expected InvalidValue, got {:?}", other.map(|_| ())),
+			}
+		}
+		assert!(config.with_env_overrides().is_ok());
+	}
+}
@@ -0,0 +1,278 @@
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use directories::ProjectDirs;
+use serde::Serialize;
+use crate::error::TaskManagerError;
+
+pub const PROJECTS_FILE: &str = "projects.json";
+pub const AUDIT_FILE: &str = "audit.log";
+
+pub fn default_data_dir() -> io::Result<PathBuf> {
+	match ProjectDirs::from("", "", "taskmanager") {
+		Some(dirs) => Ok(dirs.data_dir().to_path_buf()),
+		None => Err(io::Error::new(io::ErrorKind::NotFound, "Could not determine a home directory, pass --data-dir")),
+	}
+}
+
+#[derive(Debug)]
+pub struct DataLock {
+	file: File,
+}
+
+impl DataLock {
+	pub fn acquire(data_path: &Path) -> io::Result<DataLock> {
+		let mut lock_path = data_path.as_os_str().to_owned();
+		lock_path.push(".lock");
+		let lock_path = PathBuf::from(lock_path);
+		let file = OpenOptions::new().create(true).truncate(false).write(true).open(&lock_path)?;
+		match file.try_lock() {
+			Ok(_) => {},
+			Err(TryLockError::WouldBlock) => {
+				eprintln!("Waiting for another taskmanager process to release {}", lock_path.display());
+				file.lock()?;
+			},
+			Err(TryLockError::Error(err)) => {
+				return Err(err);
+			},
+		}
+
+		Ok(DataLock { file })
+	}
+
+	pub fn lock(&self) -> io::Result<()> {
+		self.file.lock()
+	}
+
+	pub fn release(&self) -> io::Result<()> {
+		self.file.unlock()
+	}
+}
+
+fn snapshot_path(path: &Path, index: usize) -> PathBuf {
+	let mut snapshot_path = path.as_os_str().to_owned();
+	snapshot_path.push(format!(".bak.{}", index));
+
+	PathBuf::from(snapshot_path)
+}
+
+pub fn take_snapshot(path: &Path, keep: usize) -> io::Result<()> {
+	if keep == 0 || !path.exists() {
+		return Ok(());
+	}
+
+	for index in (1..keep).rev() {
+		let snapshot = snapshot_path(path, index);
+		if snapshot.exists() {
+			fs::rename(&snapshot, snapshot_path(path, index + 1))?;
+		}
+	}
+	fs::copy(path, snapshot_path(path, 1))?;
+
+	Ok(())
+}
+
+pub fn restore_snapshot(path: &Path) -> io::Result<bool> {
+	let latest = snapshot_path(path, 1);
+	if !latest.exists() {
+		return Ok(false);
+	}
+	fs::rename(&latest, path)?;
+
+	let mut index = 2;
+	while snapshot_path(path, index).exists() {
+		fs::rename(snapshot_path(path, index), snapshot_path(path, index - 1))?;
+		index += 1;
+	}
+
+	Ok(true)
+}
+
+fn temporary_path(path: &Path) -> PathBuf {
+	let mut tmp_path = path.as_os_str().to_owned();
+	tmp_path.push(".tmp");
+
+	PathBuf::from(tmp_path)
+}
+
+pub fn backup_corrupt(path: &Path) -> io::Result<PathBuf> {
+	let mut backup_path = path.as_os_str().to_owned();
+	backup_path.push(".corrupt");
+	let backup_path = PathBuf::from(backup_path);
+	fs::rename(path, &backup_path)?;
+
+	Ok(backup_path)
+}
+
+pub fn write_json_atomic<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<(), TaskManagerError> {
+	let tmp_path = temporary_path(path);
+	let write_result = File::create(&tmp_path).map_err(TaskManagerError::from).and_then(|file| {
+		serde_json::to_writer(&file, value)?;
+		file.sync_all()?;
+
+		Ok(())
+	});
+
+	match write_result {
+		Ok(_) => {
+			fs::rename(&tmp_path, path)?;
+
+			Ok(())
+		},
+		Err(err) => {
+			let _ = fs::remove_file(&tmp_path);
+
+			Err(err)
+		},
+	}
+}
+
+pub fn append_audit_entry(path: &Path, entry: &str) -> io::Result<()> {
+	let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or_default();
+	let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+
+	writeln!(file, "{} {}", timestamp, entry)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde::Serializer;
+
+	struct Unserializable;
+
+	impl Serialize for Unserializable {
+		fn serialize<S: Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+			Err(serde::ser::Error::custom("refusing to serialize"))
+		}
+	}
+
+	#[test]
+	fn data_lock_serializes_two_writers() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join(PROJECTS_FILE);
+		fs::write(&path, "0").unwrap();
+
+		let writers: Vec<_> = (0..2).map(|_| {
+			let path = path.clone();
+			std::thread::spawn(move || {
+				let data_lock = DataLock::acquire(&path).unwrap();
+				let count: u32 = fs::read_to_string(&path).unwrap().parse().unwrap();
+				std::thread::sleep(std::time::Duration::from_millis(50));
+				fs::write(&path, (count + 1).to_string()).unwrap();
+				data_lock.release().unwrap();
+			})
+		}).collect();
+		for writer in writers {
+			writer.join().unwrap();
+		}
+
+		assert_eq!(fs::read_to_string(&path).unwrap(), "2");
+	}
+
+	#[test]
+	fn take_snapshot_rotates_and_keeps_limit() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join(PROJECTS_FILE);
+
+		for version in 1..=4 {
+			fs::write(&path, version.to_string()).unwrap();
+			take_snapshot(&path, 3).unwrap();
+		}
+
+		assert_eq!(fs::read_to_string(snapshot_path(&path, 1)).unwrap(), "4");
+		assert_eq!(fs::read_to_string(snapshot_path(&path, 2)).unwrap(), "3");
+		assert_eq!(fs::read_to_string(snapshot_path(&path, 3)).unwrap(), "2");
+		assert!(!snapshot_path(&path, 4).exists());
+	}
+
+	#[test]
+	fn take_snapshot_skips_missing_file_and_zero_keep() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join(PROJECTS_FILE);
+
+		take_snapshot(&path, 3).unwrap();
+		assert!(!snapshot_path(&path, 1).exists());
+
+		fs::write(&path, "1").unwrap();
+		take_snapshot(&path, 0).unwrap();
+		assert!(!snapshot_path(&path, 1).exists());
+	}
+
+	#[test]
+	fn restore_snapshot_shifts_older_snapshots_down() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join(PROJECTS_FILE);
+		for version in 1..=3 {
+			fs::write(&path, version.to_string()).unwrap();
+			take_snapshot(&path, 5).unwrap();
+		}
+		fs::write(&path, "4").unwrap();
+
+		assert!(restore_snapshot(&path).unwrap());
+		assert_eq!(fs::read_to_string(&path).unwrap(), "3");
+		assert_eq!(fs::read_to_string(snapshot_path(&path, 1)).unwrap(), "2");
+		assert_eq!(fs::read_to_string(snapshot_path(&path, 2)).unwrap(), "1");
+		assert!(!snapshot_path(&path, 3).exists());
+
+		assert!(restore_snapshot(&path).unwrap());
+		assert!(restore_snapshot(&path).unwrap());
+		assert_eq!(fs::read_to_string(&path).unwrap(), "1");
+		assert!(!restore_snapshot(&path).unwrap());
+	}
+
+	#[test]
+	fn backup_corrupt_renames_file() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join(PROJECTS_FILE);
+		fs::write(&path, "{not json").unwrap();
+
+		let backup_path = backup_corrupt(&path).unwrap();
+
+		assert_eq!(backup_path, dir.path().join("projects.json.corrupt"));
+		assert_eq!(fs::read_to_string(&backup_path).unwrap(), "{not json");
+		assert!(!path.exists());
+	}
+
+	#[test]
+	fn append_audit_entry_appends_in_order() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join(AUDIT_FILE);
+
+		append_audit_entry(&path, "project create 1").unwrap();
+		append_audit_entry(&path, "config set persistence_mode json").unwrap();
+
+		let contents = fs::read_to_string(&path).unwrap();
+		let entries: Vec<&str> = contents.lines()
+			.map(|line| line.split_once(' ').unwrap())
+			.inspect(|(timestamp, _)| assert!(timestamp.parse::<u64>().is_ok()))
+			.map(|(_, entry)| entry)
+			.collect();
+		assert_eq!(entries, vec!["project create 1", "config set persistence_mode json"]);
+	}
+
+	#[test]
+	fn write_json_atomic_replaces_file() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join(PROJECTS_FILE);
+		fs::write(&path, "[1]").unwrap();
+
+		write_json_atomic(&path, &vec![2, 3]).unwrap();
+
+		assert_eq!(fs::read_to_string(&path).unwrap(), "[2,3]");
+		assert!(!temporary_path(&path).exists());
+	}
+
+	#[test]
+	fn failed_write_leaves_existing_file_intact() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join(PROJECTS_FILE);
+		fs::write(&path, "[1]").unwrap();
+
+		assert!(write_json_atomic(&path, &Unserializable).is_err());
+
+		assert_eq!(fs::read_to_string(&path).unwrap(), "[1]");
+		assert!(!temporary_path(&path).exists());
+	}
+}